@@ -0,0 +1,450 @@
+//! Active abilities of the player, triggered by hotkeys.
+//!
+//! The player has [`ABILITY_SLOTS`] slots, which are filled through upgrades.
+//! Each ability has its own cooldown, shown in the ability bar at the bottom
+//! left of the screen.
+
+use std::time::Duration;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        Health,
+        enemy::Enemy,
+        events::GameEvent,
+        forward_vec,
+        hull::HullSections,
+        player::Player,
+        status_effects::{ApplyStatus, StatusEffect, StatusEffectKind},
+        terrain::waves::Waves,
+    },
+    screens::Screen,
+    theme::{palette::ABILITY_BAR, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_ability_bar);
+    app.add_systems(
+        Update,
+        (
+            use_abilities.in_set(AppSystems::RecordInput),
+            (
+                tick_cooldowns,
+                conceal_player,
+                spread_oil,
+                apply_speed_burst,
+                expire_areas,
+                update_ability_bar,
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+pub const ABILITY_SLOTS: usize = 3;
+const ABILITY_KEYS: [(KeyCode, &str); ABILITY_SLOTS] = [
+    (KeyCode::KeyQ, "Q"),
+    (KeyCode::KeyE, "E"),
+    (KeyCode::KeyR, "R"),
+];
+
+const SMOKE_RADIUS: f32 = 300.0;
+const SMOKE_SECS: f32 = 6.0;
+
+const OIL_RADIUS: f32 = 250.0;
+const OIL_SECS: f32 = 8.0;
+/// The slick only slows ships until it catches fire.
+const OIL_FUSE_SECS: f32 = 1.5;
+/// Oil is dropped this far behind the ship.
+const OIL_OFFSET: f32 = 200.0;
+const OIL_SLOW: f32 = 0.5;
+const OIL_BURN_DPS: f32 = 10.0;
+/// Time between applying the effects of the slick to the ships in it.
+const OIL_TICK_SECS: f32 = 0.25;
+
+/// Share of the maximum health restored by a repair.
+const REPAIR_FRACTION: f32 = 0.3;
+
+const BURST_SECS: f32 = 1.5;
+const BURST_FORCE: f32 = 3000.0;
+/// How hard the ship is turned towards the wave during a burst.
+const BURST_TURN: f32 = 800.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Reflect)]
+pub enum AbilityKind {
+    /// A cloud of smoke that hides the player from enemies.
+    SmokeScreen,
+    /// Oil behind the ship that slows enemies, then catches fire.
+    OilSlick,
+    /// Restores part of the hull.
+    Repair,
+    /// A short boost along the current wave direction.
+    SpeedBurst,
+}
+
+impl AbilityKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            AbilityKind::SmokeScreen => "Smoke Screen",
+            AbilityKind::OilSlick => "Oil Slick",
+            AbilityKind::Repair => "Repair",
+            AbilityKind::SpeedBurst => "Speed Burst",
+        }
+    }
+
+    fn cooldown_secs(self) -> f32 {
+        match self {
+            AbilityKind::SmokeScreen => 20.0,
+            AbilityKind::OilSlick => 15.0,
+            AbilityKind::Repair => 45.0,
+            AbilityKind::SpeedBurst => 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ability {
+    pub kind: AbilityKind,
+    cooldown: Timer,
+}
+
+impl Ability {
+    /// A new ability is ready right away.
+    pub fn new(kind: AbilityKind) -> Ability {
+        let mut cooldown = Timer::from_seconds(kind.cooldown_secs(), TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Ability { kind, cooldown }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown.is_finished()
+    }
+
+    /// How far the cooldown has progressed, 1.0 when the ability is ready.
+    pub fn charge(&self) -> f32 {
+        self.cooldown.fraction()
+    }
+}
+
+/// The ability slots of the player, bound to [`ABILITY_KEYS`].
+#[derive(Component, Debug, Default)]
+pub struct Abilities {
+    pub slots: [Option<Ability>; ABILITY_SLOTS],
+}
+
+impl Abilities {
+    pub fn has(&self, kind: AbilityKind) -> bool {
+        self.slots.iter().flatten().any(|a| a.kind == kind)
+    }
+
+    pub fn can_grant(&self, kind: AbilityKind) -> bool {
+        !self.has(kind) && self.slots.iter().any(Option::is_none)
+    }
+
+    /// Puts the ability into the first free slot, returns false if there is none.
+    pub fn grant(&mut self, kind: AbilityKind) -> bool {
+        if !self.can_grant(kind) {
+            return false;
+        }
+        let Some(slot) = self.slots.iter_mut().find(|slot| slot.is_none()) else {
+            return false;
+        };
+        *slot = Some(Ability::new(kind));
+        true
+    }
+}
+
+/// Enemies lose track of the player while it has this component.
+#[derive(Component)]
+pub struct Concealed;
+
+#[derive(Component)]
+struct SmokeCloud;
+
+#[derive(Component)]
+struct OilSlick {
+    fuse: Timer,
+    tick: Timer,
+}
+
+/// Smoke and oil disappear after a while.
+#[derive(Component)]
+struct AreaLifetime(Timer);
+
+#[derive(Component)]
+struct SpeedBurst {
+    timer: Timer,
+    direction: Vec2,
+}
+
+fn use_abilities(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    waves: Single<&Waves>,
+    player: Single<
+        (
+            Entity,
+            &mut Abilities,
+            &Transform,
+            &mut Health,
+            Option<&mut HullSections>,
+        ),
+        With<Player>,
+    >,
+    mut events: MessageWriter<GameEvent>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let (entity, mut abilities, transform, mut health, mut sections) = player.into_inner();
+    let position = transform.translation.xy();
+    for (slot, (key, _)) in abilities.slots.iter_mut().zip(ABILITY_KEYS) {
+        let Some(ability) = slot else {
+            continue;
+        };
+        if !input.just_pressed(key) || !ability.is_ready() {
+            continue;
+        }
+        ability.cooldown.reset();
+
+        match ability.kind {
+            AbilityKind::SmokeScreen => {
+                commands.spawn((
+                    Name::new("Smoke Screen"),
+                    SmokeCloud,
+                    AreaLifetime(Timer::from_seconds(SMOKE_SECS, TimerMode::Once)),
+                    Mesh2d(meshes.add(Circle::new(SMOKE_RADIUS))),
+                    MeshMaterial2d(materials.add(Color::srgba(0.75, 0.75, 0.75, 0.7))),
+                    Transform::from_translation(position.extend(2.0)),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+            }
+            AbilityKind::OilSlick => {
+                let behind = position - forward_vec(*transform) * OIL_OFFSET;
+                commands.spawn((
+                    Name::new("Oil Slick"),
+                    OilSlick {
+                        fuse: Timer::from_seconds(OIL_FUSE_SECS, TimerMode::Once),
+                        tick: Timer::from_seconds(OIL_TICK_SECS, TimerMode::Repeating),
+                    },
+                    AreaLifetime(Timer::from_seconds(OIL_SECS, TimerMode::Once)),
+                    Mesh2d(meshes.add(Circle::new(OIL_RADIUS))),
+                    MeshMaterial2d(materials.add(Color::srgba(0.15, 0.1, 0.05, 0.7))),
+                    Transform::from_translation(behind.extend(0.2)),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+            }
+            AbilityKind::Repair => {
+                let repaired = (health.max as f32 * REPAIR_FRACTION) as i32;
+                health.current = (health.current + repaired).min(health.max);
+                if let Some(sections) = &mut sections {
+                    sections.repair(repaired as f32);
+                }
+                events.write(GameEvent::PlayerHealthChanged {
+                    fraction: health.fraction(),
+                });
+            }
+            AbilityKind::SpeedBurst => {
+                // Ride along with the waves, or straight ahead on calm water.
+                let (wave_dir, _wave_height, up) = waves.wave_height(position, time.elapsed_secs());
+                let direction = if wave_dir.is_nan() || wave_dir == Vec2::ZERO {
+                    forward_vec(*transform)
+                } else if up {
+                    -wave_dir.normalize()
+                } else {
+                    wave_dir.normalize()
+                };
+                commands.entity(entity).insert(SpeedBurst {
+                    timer: Timer::from_seconds(BURST_SECS, TimerMode::Once),
+                    direction,
+                });
+            }
+        }
+    }
+}
+
+fn tick_cooldowns(time: Res<Time>, mut abilities: Query<&mut Abilities>) {
+    for mut abilities in &mut abilities {
+        for ability in abilities.slots.iter_mut().flatten() {
+            ability.cooldown.tick(time.delta());
+        }
+    }
+}
+
+fn conceal_player(
+    mut commands: Commands,
+    player: Single<(Entity, &Transform, Has<Concealed>), With<Player>>,
+    clouds: Query<&Transform, With<SmokeCloud>>,
+) {
+    let (entity, transform, concealed) = player.into_inner();
+    let position = transform.translation.xy();
+    let hidden = clouds
+        .iter()
+        .any(|cloud| cloud.translation.xy().distance(position) < SMOKE_RADIUS);
+    if hidden && !concealed {
+        commands.entity(entity).insert(Concealed);
+    } else if !hidden && concealed {
+        commands.entity(entity).remove::<Concealed>();
+    }
+}
+
+fn spread_oil(
+    time: Res<Time>,
+    player: Single<Entity, With<Player>>,
+    mut slicks: Query<(&mut OilSlick, &Transform, &MeshMaterial2d<ColorMaterial>)>,
+    enemies: Query<(Entity, &Transform), With<Enemy>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut status: MessageWriter<ApplyStatus>,
+) {
+    let tick = Duration::from_secs_f32(OIL_TICK_SECS * 2.0);
+    for (mut slick, transform, material) in &mut slicks {
+        slick.fuse.tick(time.delta());
+        slick.tick.tick(time.delta());
+        if slick.fuse.just_finished()
+            && let Some(m) = materials.get_mut(material.0.id())
+        {
+            m.color = Color::srgba(0.95, 0.4, 0.1, 0.7);
+        }
+        if !slick.tick.just_finished() {
+            continue;
+        }
+
+        let center = transform.translation.xy();
+        for (enemy, enemy_transform) in &enemies {
+            if enemy_transform.translation.xy().distance(center) > OIL_RADIUS {
+                continue;
+            }
+            status.write(ApplyStatus {
+                target: enemy,
+                effect: StatusEffect::new(StatusEffectKind::Slowed, tick, OIL_SLOW, Some(*player)),
+            });
+            if slick.fuse.is_finished() {
+                status.write(ApplyStatus {
+                    target: enemy,
+                    effect: StatusEffect::new(
+                        StatusEffectKind::Burning,
+                        tick,
+                        OIL_BURN_DPS,
+                        Some(*player),
+                    ),
+                });
+            }
+        }
+    }
+}
+
+fn apply_speed_burst(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut ships: Query<(Entity, &mut SpeedBurst, &Transform, Forces)>,
+) {
+    for (entity, mut burst, transform, mut forces) in &mut ships {
+        burst.timer.tick(time.delta());
+        if burst.timer.is_finished() {
+            commands.entity(entity).remove::<SpeedBurst>();
+            continue;
+        }
+        // Ships only sail along their heading, so turn into the wave as well.
+        let forward = forward_vec(*transform);
+        forces.apply_angular_impulse(forward.angle_to(burst.direction) * BURST_TURN);
+        forces.apply_force(burst.direction * BURST_FORCE);
+    }
+}
+
+fn expire_areas(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut areas: Query<(Entity, &mut AreaLifetime)>,
+) {
+    for (entity, mut lifetime) in &mut areas {
+        lifetime.0.tick(time.delta());
+        if lifetime.0.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[derive(Component)]
+struct AbilityLabel(usize);
+
+#[derive(Component)]
+struct AbilityCooldown(usize);
+
+fn spawn_ability_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Ability Bar"),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: px(16),
+                left: px(16),
+                flex_direction: FlexDirection::Column,
+                row_gap: px(8),
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .with_children(|parent| {
+            for (i, (_, key)) in ABILITY_KEYS.iter().enumerate() {
+                parent.spawn((
+                    Name::new("Ability Slot"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: px(2),
+                        ..default()
+                    },
+                    children![
+                        (widget::label(format!("{key}: -")), AbilityLabel(i)),
+                        (
+                            widget::bar("Ability Cooldown", px(180), px(8), ABILITY_BAR),
+                            AbilityCooldown(i),
+                        ),
+                    ],
+                ));
+            }
+        });
+}
+
+fn update_ability_bar(
+    abilities: Single<&Abilities, With<Player>>,
+    mut labels: Query<(&AbilityLabel, &mut Text)>,
+    mut bars: Query<(&AbilityCooldown, &mut Bar)>,
+) {
+    for (AbilityLabel(i), mut text) in &mut labels {
+        let name = abilities.slots[*i].as_ref().map_or("-", |a| a.kind.name());
+        let label = format!("{}: {name}", ABILITY_KEYS[*i].1);
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+    for (AbilityCooldown(i), mut bar) in &mut bars {
+        let charge = abilities.slots[*i].as_ref().map_or(0.0, Ability::charge);
+        if bar.0 != charge {
+            bar.0 = charge;
+        }
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::abilities::{Abilities, AbilityKind};
+
+    #[test]
+    fn granting_fills_free_slots() {
+        let mut abilities = Abilities::default();
+        assert!(abilities.grant(AbilityKind::Repair));
+        assert!(!abilities.grant(AbilityKind::Repair));
+        assert!(abilities.grant(AbilityKind::SmokeScreen));
+        assert!(abilities.grant(AbilityKind::SpeedBurst));
+        assert!(!abilities.can_grant(AbilityKind::OilSlick));
+        assert!(abilities.slots.iter().flatten().all(|a| a.is_ready()));
+    }
+}
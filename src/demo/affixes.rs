@@ -0,0 +1,325 @@
+//! Elite enemies with random modifiers.
+//!
+//! Enemies spawned from a marker may roll [`Affix`]es, more likely in later
+//! waves. Each affix is applied as its own component, so the systems handling
+//! an affix only have to look for that component. Adding an affix means adding
+//! a variant, its component in [`Affix::insert`] and the systems using it.
+
+use bevy::prelude::*;
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        Health,
+        damage::{DamageEvent, DamageKind, Destroyed, apply_damage},
+        enemy::{Emerging, EnemyMaterial, enemy},
+        movement::MovementController,
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            apply_affixes,
+            regenerate,
+            vampiric_drain.before(apply_damage),
+            split_on_death.after_ignore_deferred(apply_damage),
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Chance of an enemy rolling an affix is this much higher each wave.
+const AFFIX_CHANCE_PER_WAVE: f32 = 0.04;
+const MAX_AFFIX_CHANCE: f32 = 0.6;
+/// Later waves can roll more than one affix on the same enemy.
+const MAX_AFFIXES: usize = 3;
+const WAVES_PER_EXTRA_AFFIX: u32 = 6;
+
+const SWIFT_FACTOR: f32 = 1.5;
+const SPLIT_COUNT: usize = 2;
+const SPLIT_OFFSET: f32 = 70.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum Affix {
+    Armored,
+    Swift,
+    Regenerating,
+    Splitting,
+    Shielded,
+    Vampiric,
+}
+
+impl Affix {
+    pub const ALL: [Affix; 6] = [
+        Affix::Armored,
+        Affix::Swift,
+        Affix::Regenerating,
+        Affix::Splitting,
+        Affix::Shielded,
+        Affix::Vampiric,
+    ];
+
+    fn color(self) -> Srgba {
+        match self {
+            Affix::Armored => Srgba::rgb(0.55, 0.6, 0.65),
+            Affix::Swift => Srgba::rgb(0.95, 0.85, 0.2),
+            Affix::Regenerating => Srgba::rgb(0.3, 0.85, 0.35),
+            Affix::Splitting => Srgba::rgb(0.85, 0.45, 0.9),
+            Affix::Shielded => Srgba::rgb(0.3, 0.55, 0.95),
+            Affix::Vampiric => Srgba::rgb(0.7, 0.05, 0.15),
+        }
+    }
+
+    /// Adjusts how fast the ship moves.
+    pub fn adjust_movement(self, controller: &mut MovementController) {
+        if self == Affix::Swift {
+            controller.affix_multiplier *= SWIFT_FACTOR;
+        }
+    }
+
+    /// Inserts the component carrying the behavior of the affix.
+    fn insert(self, entity: &mut EntityCommands) {
+        match self {
+            Affix::Armored => entity.insert(Armored { reduction: 0.4 }),
+            Affix::Swift => entity.insert(Swift),
+            Affix::Regenerating => entity.insert(Regenerating {
+                fraction_per_second: 0.05,
+                pending: 0.0,
+            }),
+            Affix::Splitting => entity.insert(Splitting),
+            Affix::Shielded => entity.insert(FrontShield {
+                half_angle: 60f32.to_radians(),
+                reduction: 0.8,
+            }),
+            Affix::Vampiric => entity.insert(Vampiric {
+                drain_radius: 300.0,
+                drain_dps: 10.0,
+                drain_tick: Timer::from_seconds(0.5, TimerMode::Repeating),
+                life_steal: 1.0,
+            }),
+        };
+    }
+}
+
+/// The affixes an enemy rolled. The behavior lives in the components inserted
+/// for each affix, this is what the rest of the game can look at.
+#[derive(Component, Debug, Clone, Default)]
+pub struct Affixes(pub Vec<Affix>);
+
+/// Rolls the affixes of an enemy spawned in `wave`.
+pub fn roll_affixes(wave: u32, rng: &mut impl Rng) -> Vec<Affix> {
+    let chance = (wave.saturating_sub(1) as f32 * AFFIX_CHANCE_PER_WAVE).min(MAX_AFFIX_CHANCE);
+    let max = (1 + wave / WAVES_PER_EXTRA_AFFIX) as usize;
+    let mut affixes = Vec::new();
+    while affixes.len() < max.min(MAX_AFFIXES) && rng.random::<f32>() < chance {
+        let remaining: Vec<Affix> = Affix::ALL
+            .into_iter()
+            .filter(|affix| !affixes.contains(affix))
+            .collect();
+        let Some(affix) = remaining.choose(rng) else {
+            break;
+        };
+        affixes.push(*affix);
+    }
+    affixes
+}
+
+/// Takes less damage from everything.
+#[derive(Component)]
+pub struct Armored {
+    pub reduction: f32,
+}
+
+/// Faster, see [`SWIFT_FACTOR`].
+#[derive(Component)]
+pub struct Swift;
+
+/// Heals a share of its max health every second.
+#[derive(Component)]
+pub struct Regenerating {
+    pub fraction_per_second: f32,
+    /// Healing below a full hit point, carried over to the next frame.
+    pending: f32,
+}
+
+/// Breaks apart into smaller ships when sunk.
+#[derive(Component)]
+pub struct Splitting;
+
+/// Hits from within `half_angle` of the bow are mostly deflected.
+#[derive(Component)]
+pub struct FrontShield {
+    pub half_angle: f32,
+    pub reduction: f32,
+}
+
+/// Drains health from the player when close, and heals from damage it deals.
+#[derive(Component)]
+pub struct Vampiric {
+    pub drain_radius: f32,
+    pub drain_dps: f32,
+    drain_tick: Timer,
+    /// Share of the dealt damage that is healed.
+    pub life_steal: f32,
+}
+
+fn apply_affixes(
+    mut commands: Commands,
+    mut enemies: Query<
+        (
+            Entity,
+            &Affixes,
+            &MeshMaterial2d<EnemyMaterial>,
+            &mut MovementController,
+        ),
+        Added<Affixes>,
+    >,
+    mut materials: ResMut<Assets<EnemyMaterial>>,
+) {
+    for (entity, affixes, material, mut controller) in &mut enemies {
+        if affixes.0.is_empty() {
+            continue;
+        }
+        let mut entity = commands.entity(entity);
+        for affix in &affixes.0 {
+            affix.insert(&mut entity);
+            affix.adjust_movement(&mut controller);
+        }
+
+        // Ships with several affixes get a blend of their colors.
+        let color = affixes
+            .0
+            .iter()
+            .map(|affix| affix.color().to_vec3())
+            .sum::<Vec3>()
+            / affixes.0.len() as f32;
+        if let Some(m) = materials.get_mut(material.0.id()) {
+            m.tint = color.extend(1.0);
+        }
+    }
+}
+
+fn regenerate(time: Res<Time>, mut enemies: Query<(&mut Regenerating, &mut Health)>) {
+    for (mut regenerating, mut health) in &mut enemies {
+        if health.current >= health.max {
+            regenerating.pending = 0.0;
+            continue;
+        }
+        regenerating.pending +=
+            health.max as f32 * regenerating.fraction_per_second * time.delta_secs();
+        let heal = regenerating.pending.floor();
+        regenerating.pending -= heal;
+        health.current = (health.current + heal as i32).min(health.max);
+    }
+}
+
+fn vampiric_drain(
+    time: Res<Time>,
+    player: Single<(Entity, &Transform), With<Player>>,
+    mut enemies: Query<(Entity, &mut Vampiric, &Transform), Without<Emerging>>,
+    mut damage: MessageWriter<DamageEvent>,
+) {
+    let (player, player_transform) = player.into_inner();
+    let player_position = player_transform.translation.xy();
+    for (entity, mut vampiric, transform) in &mut enemies {
+        vampiric.drain_tick.tick(time.delta());
+        if vampiric.drain_tick.just_finished()
+            && transform.translation.xy().distance(player_position) < vampiric.drain_radius
+        {
+            damage.write(DamageEvent {
+                target: player,
+                amount: vampiric.drain_dps * vampiric.drain_tick.duration().as_secs_f32(),
+                kind: DamageKind::Drain,
+                source: Some(entity),
+                position: None,
+                weapon: None,
+            });
+        }
+    }
+}
+
+/// Runs right after the damage is applied, before the sunk ships are despawned.
+fn split_on_death(
+    mut commands: Commands,
+    mut destroyed: MessageReader<Destroyed>,
+    splitting: Query<&Transform, With<Splitting>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<EnemyMaterial>>,
+) {
+    for event in destroyed.read() {
+        let Ok(transform) = splitting.get(event.entity) else {
+            continue;
+        };
+        for i in 0..SPLIT_COUNT {
+            let angle = std::f32::consts::TAU * i as f32 / SPLIT_COUNT as f32;
+            let offset = Vec2::from_angle(angle) * SPLIT_OFFSET;
+            let part = Transform::from_translation(
+                (event.position + offset).extend(transform.translation.z),
+            )
+            .with_rotation(transform.rotation)
+            .with_scale(Vec3::splat(0.7));
+            commands
+                .spawn(enemy(part, &mut meshes, &mut materials))
+                .insert((
+                    Name::new("Raider Fragment"),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+        }
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[allow(unused)]
+    use crate::demo::{
+        affixes::{Affix, MAX_AFFIXES, SWIFT_FACTOR, roll_affixes},
+        fire_ship::ramp_speed,
+        movement::MovementController,
+        steering::Steering,
+    };
+
+    #[test]
+    fn affixes_scale_with_waves() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let rolled = |wave: u32, rng: &mut StdRng| {
+            (0..1000)
+                .map(|_| roll_affixes(wave, rng))
+                .filter(|affixes| !affixes.is_empty())
+                .count()
+        };
+        assert_eq!(rolled(1, &mut rng), 0);
+        assert!(rolled(5, &mut rng) < rolled(15, &mut rng));
+
+        for _ in 0..1000 {
+            let mut affixes = roll_affixes(40, &mut rng);
+            assert!(affixes.len() <= MAX_AFFIXES);
+            let len = affixes.len();
+            affixes.sort_by_key(|affix| *affix as u8);
+            affixes.dedup();
+            assert_eq!(affixes.len(), len, "no duplicate affixes");
+        }
+    }
+
+    #[test]
+    fn swift_fire_ships_are_faster() {
+        let mut plain = MovementController::default();
+        let mut swift = MovementController::default();
+        Affix::Swift.adjust_movement(&mut swift);
+        Affix::Armored.adjust_movement(&mut plain);
+        // The ramp sets the base speed every frame and must not undo the affix.
+        for t in [0.0, 0.5, 1.0] {
+            ramp_speed(t, &mut Steering::default(), &mut plain);
+            ramp_speed(t, &mut Steering::default(), &mut swift);
+            assert_eq!(swift.top_speed(), plain.top_speed() * SWIFT_FACTOR);
+        }
+    }
+}
@@ -0,0 +1,439 @@
+//! Boss ships leading milestone waves.
+//!
+//! A boss moves through its [`BossPhase`]s as its hull gets damaged. Each phase
+//! keeps the attacks of the previous ones: broadsides from the start, bow
+//! chasers and summoned escorts below two thirds of its health, and ramming
+//! charges below one third.
+
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        GameCollisionLayer, Health,
+        abilities::Concealed,
+        damage::{DamageEvent, DamageKind},
+        enemy::{
+            Emerging, Enemy, EnemyKind, EnemyMaterial, PositionRecording, enemy_collision_layers,
+        },
+        hull::{HullSection, HullSections},
+        movement::MovementController,
+        player::Player,
+        spawning::{SpawnMarkerMaterial, spawn_marker},
+        status_effects::{ApplyStatus, InflictsStatus, StatusEffect, StatusEffectKind},
+        steering::Steering,
+        weapons::{WeaponMaterial, cannon_ball},
+    },
+    screens::Screen,
+    theme::{palette::HEALTH_BAR, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            update_boss_phase,
+            fire_hardpoints,
+            summon_escorts,
+            ram_player,
+            spawn_boss_bar,
+            update_boss_bar,
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const BOSS_NAME: &str = "Dread Galleon";
+const BOSS_HEALTH: i32 = 3000;
+const BOSS_THRUST: f32 = 60.0;
+const BOSS_SPEED: f32 = 200.0;
+
+const HARDPOINT_RANGE: f32 = 1400.0;
+const HARDPOINT_BALL_SPEED: f32 = 400.0;
+
+const ESCORTS_PER_SUMMON: usize = 3;
+const ESCORT_DISTANCE: f32 = 350.0;
+
+const RAM_CHARGE_SECS: f32 = 2.0;
+const RAM_THRUST_FACTOR: f32 = 3.0;
+const RAM_REACH: f32 = 220.0;
+const RAM_DAMAGE: f32 = 80.0;
+const RAM_STUN_SECS: f32 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BossPhase {
+    Broadsides,
+    Escorts,
+    Ramming,
+}
+
+impl BossPhase {
+    fn from_health(health: &Health) -> BossPhase {
+        let fraction = health.fraction();
+        if fraction > 2.0 / 3.0 {
+            BossPhase::Broadsides
+        } else if fraction > 1.0 / 3.0 {
+            BossPhase::Escorts
+        } else {
+            BossPhase::Ramming
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub phase: BossPhase,
+    summon: Timer,
+    ram_cooldown: Timer,
+    /// Running while the boss is charging at the player.
+    charge: Option<Timer>,
+    /// Whether the current charge already hit the player.
+    charge_landed: bool,
+}
+
+impl Default for Boss {
+    fn default() -> Self {
+        Self {
+            phase: BossPhase::Broadsides,
+            summon: Timer::from_seconds(12.0, TimerMode::Repeating),
+            ram_cooldown: Timer::from_seconds(6.0, TimerMode::Repeating),
+            charge: None,
+            charge_landed: false,
+        }
+    }
+}
+
+/// A gun mounted on a boss, aiming at the player on its own.
+#[derive(Component)]
+pub struct Hardpoint {
+    pub cooldown: Timer,
+    pub damage: f32,
+    /// Put on the player by the balls of this hardpoint.
+    pub inflicts: Option<StatusEffect>,
+    /// The hardpoint stays quiet before the boss reaches this phase.
+    pub active_from: BossPhase,
+    /// The hardpoint stays quiet once this section is destroyed.
+    pub section: HullSection,
+}
+
+impl Hardpoint {
+    fn new(secs: f32, damage: f32, active_from: BossPhase, section: HullSection) -> Hardpoint {
+        Hardpoint {
+            cooldown: Timer::from_seconds(secs, TimerMode::Repeating),
+            damage,
+            inflicts: None,
+            active_from,
+            section,
+        }
+    }
+
+    fn inflicting(mut self, effect: StatusEffect) -> Hardpoint {
+        self.inflicts = Some(effect);
+        self
+    }
+}
+
+fn hull_section(name: &'static str, x: f32, y: f32, width: f32, length: f32) -> impl Bundle {
+    (
+        Name::new(name),
+        Transform::from_xyz(x, y, 0.0),
+        Collider::rectangle(width, length),
+        enemy_collision_layers(),
+    )
+}
+
+fn hardpoint(name: &'static str, x: f32, y: f32, hardpoint: Hardpoint) -> impl Bundle {
+    (Name::new(name), Transform::from_xyz(x, y, 0.0), hardpoint)
+}
+
+/// A boss ship. Its hull is made from several colliders, so it can be hit along
+/// its whole length.
+pub fn boss(
+    transform: Transform,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<EnemyMaterial>,
+) -> impl Bundle {
+    // The boat shader draws the hull across the middle 14% x 30% of the mesh.
+    let mesh = meshes.add(Rectangle::new(1000.0, 1000.0));
+    let material = materials.add(EnemyMaterial::default());
+    (
+        Name::new("Boss"),
+        Enemy,
+        EnemyKind::Boss,
+        Boss::default(),
+        Mesh2d(mesh),
+        MeshMaterial2d(material),
+        transform,
+        (
+            RigidBody::Dynamic,
+            Mass(100.0),
+            AngularDamping(4.0),
+            LinearDamping(0.5),
+        ),
+        MovementController {
+            max_speed: BOSS_SPEED,
+            ..default()
+        },
+        PositionRecording::new(transform),
+        (Health::new(BOSS_HEALTH), HullSections::new(BOSS_HEALTH)),
+        Steering {
+            thrust: BOSS_THRUST,
+            separation_radius: 250.0,
+            avoidance_radius: 120.0,
+            avoidance_distance: 600.0,
+            ..default()
+        },
+        Emerging::default(),
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            hull_section("Bow", 0.0, 100.0, 90.0, 100.0),
+            hull_section("Midships", 0.0, 0.0, 140.0, 100.0),
+            hull_section("Stern", 0.0, -100.0, 120.0, 100.0),
+            hardpoint(
+                "Port Battery",
+                -60.0,
+                20.0,
+                Hardpoint::new(2.5, 25.0, BossPhase::Broadsides, HullSection::Port)
+            ),
+            hardpoint(
+                "Starboard Battery",
+                60.0,
+                20.0,
+                Hardpoint::new(2.5, 25.0, BossPhase::Broadsides, HullSection::Starboard)
+            ),
+            hardpoint(
+                "Stern Gun",
+                0.0,
+                -140.0,
+                Hardpoint::new(4.0, 40.0, BossPhase::Broadsides, HullSection::Stern).inflicting(
+                    StatusEffect::new(StatusEffectKind::Leaking, Duration::from_secs(6), 3.0, None,)
+                )
+            ),
+            hardpoint(
+                "Bow Chaser",
+                0.0,
+                140.0,
+                Hardpoint::new(1.2, 20.0, BossPhase::Escorts, HullSection::Bow)
+            ),
+        ],
+    )
+}
+
+fn update_boss_phase(mut bosses: Query<(&mut Boss, &Health)>) {
+    for (mut boss, health) in &mut bosses {
+        // Phases only ever advance, even if the boss regains some health.
+        let phase = BossPhase::from_health(health);
+        if phase > boss.phase {
+            boss.phase = phase;
+        }
+    }
+}
+
+fn fire_hardpoints(
+    time: Res<Time>,
+    mut commands: Commands,
+    player: Single<(&Transform, Has<Concealed>), With<Player>>,
+    bosses: Query<(Entity, &Boss, &HullSections, &Children), Without<Emerging>>,
+    mut hardpoints: Query<(&mut Hardpoint, &GlobalTransform)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<WeaponMaterial>>,
+) {
+    let (player, concealed) = player.into_inner();
+    let player = player.translation.xy();
+    for (entity, boss, sections, children) in &bosses {
+        for child in children {
+            let Ok((mut hardpoint, transform)) = hardpoints.get_mut(*child) else {
+                continue;
+            };
+            if boss.phase < hardpoint.active_from || sections.is_destroyed(hardpoint.section) {
+                continue;
+            }
+            hardpoint.cooldown.tick(time.delta());
+            if !hardpoint.cooldown.just_finished() {
+                continue;
+            }
+
+            let origin = transform.translation().xy();
+            let to_player = player - origin;
+            if concealed || to_player.length() > HARDPOINT_RANGE {
+                continue;
+            }
+            let ball_transform = Transform::from_translation(origin.extend(0.0))
+                .with_rotation(Quat::from_rotation_z(to_player.to_angle() - FRAC_PI_2));
+            let mut ball = commands.spawn(cannon_ball(
+                ball_transform,
+                HARDPOINT_BALL_SPEED,
+                hardpoint.damage,
+                GameCollisionLayer::Player,
+                entity,
+                &mut meshes,
+                &mut materials,
+            ));
+            if let Some(effect) = &hardpoint.inflicts {
+                ball.insert(InflictsStatus(effect.clone()));
+            }
+        }
+    }
+}
+
+fn summon_escorts(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut bosses: Query<(&mut Boss, &Transform)>,
+    spatial_query: SpatialQuery,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<SpawnMarkerMaterial>>,
+) {
+    let terrain = SpatialQueryFilter::from_mask(GameCollisionLayer::Terrain);
+    for (mut boss, transform) in &mut bosses {
+        if boss.phase < BossPhase::Escorts {
+            continue;
+        }
+        boss.summon.tick(time.delta());
+        if !boss.summon.just_finished() {
+            continue;
+        }
+
+        let center = transform.translation.xy();
+        for i in 0..ESCORTS_PER_SUMMON {
+            let angle = std::f32::consts::TAU * i as f32 / ESCORTS_PER_SUMMON as f32;
+            let point = center + Vec2::from_angle(angle) * ESCORT_DISTANCE;
+            if !spatial_query
+                .point_intersections(point, &terrain)
+                .is_empty()
+            {
+                continue;
+            }
+            commands.spawn(spawn_marker(
+                EnemyKind::Raider,
+                point,
+                transform.rotation,
+                &mut meshes,
+                &mut materials,
+            ));
+        }
+    }
+}
+
+fn ram_player(
+    time: Res<Time>,
+    player: Single<(Entity, &Transform), With<Player>>,
+    mut bosses: Query<
+        (
+            Entity,
+            &mut Boss,
+            &mut Steering,
+            &mut MovementController,
+            &Transform,
+        ),
+        Without<Player>,
+    >,
+    mut damage: MessageWriter<DamageEvent>,
+    mut status: MessageWriter<ApplyStatus>,
+) {
+    let (player, player_transform) = player.into_inner();
+    let player_position = player_transform.translation.xy();
+
+    for (entity, mut boss, mut steering, mut controller, transform) in &mut bosses {
+        if boss.phase < BossPhase::Ramming {
+            continue;
+        }
+
+        let Some(charge) = &mut boss.charge else {
+            boss.ram_cooldown.tick(time.delta());
+            if boss.ram_cooldown.just_finished() {
+                boss.charge = Some(Timer::from_seconds(RAM_CHARGE_SECS, TimerMode::Once));
+                boss.charge_landed = false;
+                steering.thrust = BOSS_THRUST * RAM_THRUST_FACTOR;
+                controller.max_speed = BOSS_SPEED * RAM_THRUST_FACTOR;
+            }
+            continue;
+        };
+
+        charge.tick(time.delta());
+        let finished = charge.is_finished();
+        if !boss.charge_landed && transform.translation.xy().distance(player_position) < RAM_REACH {
+            damage.write(DamageEvent {
+                target: player,
+                amount: RAM_DAMAGE,
+                kind: DamageKind::Ram,
+                source: Some(entity),
+                position: None,
+                weapon: None,
+            });
+            status.write(ApplyStatus {
+                target: player,
+                effect: StatusEffect::new(
+                    StatusEffectKind::Stunned,
+                    Duration::from_secs_f32(RAM_STUN_SECS),
+                    1.0,
+                    Some(entity),
+                ),
+            });
+            boss.charge_landed = true;
+        }
+        if finished {
+            boss.charge = None;
+            steering.thrust = BOSS_THRUST;
+            controller.max_speed = BOSS_SPEED;
+        }
+    }
+}
+
+/// The root of the boss health bar UI.
+#[derive(Component)]
+struct BossBar(Entity);
+
+/// The bar showing the health of a boss.
+#[derive(Component)]
+struct BossHealthBar(Entity);
+
+fn spawn_boss_bar(mut commands: Commands, bosses: Query<Entity, Added<Boss>>) {
+    for boss in &bosses {
+        commands.spawn((
+            Name::new("Boss Bar"),
+            BossBar(boss),
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(20),
+                width: percent(100),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: px(6),
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+            children![
+                widget::label(BOSS_NAME),
+                (
+                    widget::bar("Boss Health", px(600), px(20), HEALTH_BAR),
+                    BossHealthBar(boss),
+                ),
+            ],
+        ));
+    }
+}
+
+fn update_boss_bar(
+    mut commands: Commands,
+    roots: Query<(Entity, &BossBar)>,
+    mut bars: Query<(&mut Bar, &BossHealthBar)>,
+    bosses: Query<&Health, With<Boss>>,
+) {
+    for (mut bar, BossHealthBar(boss)) in &mut bars {
+        if let Ok(health) = bosses.get(*boss) {
+            bar.0 = health.fraction();
+        }
+    }
+    for (root, BossBar(boss)) in &roots {
+        if !bosses.contains(*boss) {
+            commands.entity(root).despawn();
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! The camera following the player's ship.
+//!
+//! The camera trails the ship with some damping and looks ahead in the
+//! direction it is sailing. It zooms out the faster the ship goes and the
+//! more enemies are around, and can optionally turn with the ship so that its
+//! heading always points up.
+//!
+//! Hits and explosions shake the screen by writing [`ScreenShake`] messages.
+//! Shakes add up to a trauma value that decays over time, and the shake grows
+//! with the square of the trauma, so small bumps stay subtle.
+
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{Health, damage::Damaged, enemy::Enemy, movement::MovementController, player::Player},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CameraSettings>();
+    app.init_resource::<CameraRig>();
+    app.add_message::<ScreenShake>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_rig);
+    app.add_systems(OnExit(Screen::Gameplay), reset_camera);
+    app.add_systems(
+        Update,
+        (shake_on_player_hits, follow_player, apply_camera_rig)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How quickly the camera catches up with its target, higher is faster.
+const FOLLOW_DECAY: f32 = 4.0;
+const ROTATION_DECAY: f32 = 3.0;
+const ZOOM_DECAY: f32 = 1.5;
+/// The camera leads the ship by where it will be in this many seconds.
+const LOOK_AHEAD_SECS: f32 = 0.6;
+const MAX_LOOK_AHEAD: f32 = 300.0;
+/// Extra zoom at full speed.
+const SPEED_ZOOM: f32 = 0.4;
+/// Extra zoom per enemy close to the player.
+const ENEMY_ZOOM: f32 = 0.04;
+const MAX_ZOOM: f32 = 1.8;
+const DENSITY_RADIUS: f32 = 1500.0;
+
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.2;
+const MAX_SHAKE_OFFSET: f32 = 40.0;
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+/// Shakes with an origin fade out up to this distance from the camera.
+const SHAKE_RADIUS: f32 = 2000.0;
+/// Trauma from losing this share of the hull in one hit.
+const HIT_TRAUMA_PER_HULL: f32 = 4.0;
+
+#[derive(Resource, Debug)]
+pub struct CameraSettings {
+    pub screen_shake: bool,
+    /// Turn the camera with the ship, so its heading always points up.
+    pub heading_up: bool,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            screen_shake: true,
+            heading_up: false,
+        }
+    }
+}
+
+/// Shakes the screen.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ScreenShake {
+    /// Between 0.0 and 1.0, a full trauma is the strongest shake.
+    pub trauma: f32,
+    /// Where the shake comes from. The further away from the camera, the
+    /// weaker the shake.
+    pub origin: Option<Vec2>,
+}
+
+/// The smoothed state of the camera, before shaking.
+#[derive(Resource, Debug, Default)]
+struct CameraRig {
+    position: Vec2,
+    rotation: f32,
+    zoom: f32,
+    trauma: f32,
+    /// Snap to the player instead of easing towards it on the next update.
+    snap: bool,
+}
+
+/// How far out the camera zooms for the ship going at `speed_fraction` of its
+/// maximum speed with `enemies` enemies around.
+fn target_zoom(speed_fraction: f32, enemies: usize) -> f32 {
+    (1.0 + speed_fraction.clamp(0.0, 1.0) * SPEED_ZOOM + enemies as f32 * ENEMY_ZOOM).min(MAX_ZOOM)
+}
+
+/// Share of the maximum shake at the given trauma.
+fn shake_amount(trauma: f32) -> f32 {
+    trauma.clamp(0.0, 1.0).powi(2)
+}
+
+fn reset_rig(mut rig: ResMut<CameraRig>) {
+    *rig = CameraRig {
+        zoom: 1.0,
+        snap: true,
+        ..default()
+    };
+}
+
+/// Menus and other screens expect the camera where it started.
+fn reset_camera(camera: Single<(&mut Transform, &mut Projection), With<Camera2d>>) {
+    let (mut transform, mut projection) = camera.into_inner();
+    *transform = Transform::default();
+    if let Projection::Orthographic(orthographic) = projection.as_mut() {
+        orthographic.scale = 1.0;
+    }
+}
+
+fn shake_on_player_hits(
+    mut damaged: MessageReader<Damaged>,
+    player: Single<(Entity, &Health), With<Player>>,
+    mut shake: MessageWriter<ScreenShake>,
+) {
+    let (player, health) = player.into_inner();
+    for event in damaged.read() {
+        if event.entity == player {
+            shake.write(ScreenShake {
+                trauma: event.amount as f32 / health.max as f32 * HIT_TRAUMA_PER_HULL,
+                origin: None,
+            });
+        }
+    }
+}
+
+fn follow_player(
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    mut rig: ResMut<CameraRig>,
+    mut shakes: MessageReader<ScreenShake>,
+    player: Single<(&Transform, &LinearVelocity, &MovementController), With<Player>>,
+    enemies: Query<&Transform, With<Enemy>>,
+) {
+    let (transform, velocity, controller) = player.into_inner();
+    let dt = time.delta_secs();
+    let position = transform.translation.xy();
+
+    let look_ahead = (velocity.0 * LOOK_AHEAD_SECS).clamp_length_max(MAX_LOOK_AHEAD);
+    let target = position + look_ahead;
+    let target_rotation = if settings.heading_up {
+        transform.rotation.to_euler(EulerRot::XYZ).2
+    } else {
+        0.0
+    };
+    let nearby = enemies
+        .iter()
+        .filter(|enemy| enemy.translation.xy().distance(position) < DENSITY_RADIUS)
+        .count();
+    let zoom = target_zoom(velocity.length() / controller.max_speed.max(1.0), nearby);
+
+    if rig.snap {
+        rig.snap = false;
+        rig.position = target;
+        rig.rotation = target_rotation;
+        rig.zoom = zoom;
+    } else {
+        rig.position.smooth_nudge(&target, FOLLOW_DECAY, dt);
+        // Ease along the shorter way around.
+        let mut rotation_delta = Rot2::radians(target_rotation - rig.rotation).as_radians();
+        rotation_delta *= 1.0 - (-ROTATION_DECAY * dt).exp();
+        rig.rotation += rotation_delta;
+        rig.zoom.smooth_nudge(&zoom, ZOOM_DECAY, dt);
+    }
+
+    let camera = rig.position;
+    let mut trauma = rig.trauma - TRAUMA_DECAY * dt;
+    for shake in shakes.read() {
+        let falloff = shake.origin.map_or(1.0, |origin| {
+            1.0 - (origin.distance(camera) / SHAKE_RADIUS).min(1.0)
+        });
+        trauma += shake.trauma * falloff;
+    }
+    rig.trauma = trauma.clamp(0.0, 1.0);
+}
+
+fn apply_camera_rig(
+    rig: Res<CameraRig>,
+    settings: Res<CameraSettings>,
+    camera: Single<(&mut Transform, &mut Projection), (With<Camera2d>, Without<Player>)>,
+) {
+    let (mut transform, mut projection) = camera.into_inner();
+    let mut offset = Vec2::ZERO;
+    let mut angle = 0.0;
+    if settings.screen_shake {
+        let shake = shake_amount(rig.trauma);
+        let mut rng = rand::rng();
+        offset = Vec2::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0))
+            * MAX_SHAKE_OFFSET
+            * shake;
+        angle = rng.random_range(-1.0..1.0) * MAX_SHAKE_ANGLE * shake;
+    }
+
+    transform.translation = (rig.position + offset).extend(transform.translation.z);
+    transform.rotation = Quat::from_rotation_z(rig.rotation + angle);
+    if let Projection::Orthographic(orthographic) = projection.as_mut() {
+        orthographic.scale = rig.zoom;
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::camera::{MAX_ZOOM, shake_amount, target_zoom};
+
+    #[test]
+    fn zoom_and_shake_stay_in_bounds() {
+        assert_eq!(target_zoom(0.0, 0), 1.0);
+        assert!(target_zoom(1.0, 0) > target_zoom(0.5, 0));
+        assert!(target_zoom(0.5, 5) > target_zoom(0.5, 0));
+        assert_eq!(target_zoom(2.0, 1000), MAX_ZOOM);
+
+        assert_eq!(shake_amount(0.5), 0.25);
+        assert_eq!(shake_amount(3.0), 1.0);
+        assert_eq!(shake_amount(-1.0), 0.0);
+    }
+}
@@ -0,0 +1,180 @@
+//! All damage goes through [`DamageEvent`]s, so that effects like
+//! invulnerability only have to be handled in one place, and other systems can
+//! react to damage and destroyed ships.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        Health,
+        affixes::{Armored, FrontShield, Vampiric},
+        enemy::{Emerging, EnemyKind},
+        events::GameEvent,
+        forward_vec,
+        hull::{HullSection, HullSections},
+        player::Player,
+        weapons::WeaponKind,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<DamageEvent>();
+    app.add_message::<Damaged>();
+    app.add_message::<Destroyed>();
+    app.add_systems(
+        Update,
+        apply_damage
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    Cannon,
+    Explosion,
+    Burning,
+    Ram,
+    Drain,
+    Leaking,
+}
+
+#[derive(Message, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub kind: DamageKind,
+    /// The ship responsible for the damage, if any.
+    pub source: Option<Entity>,
+    /// Where the ship was hit, `None` for damage without a point of impact,
+    /// like fires and leaks.
+    pub position: Option<Vec2>,
+    /// The player's weapon that dealt the damage.
+    pub weapon: Option<WeaponKind>,
+}
+
+/// Damage that actually made it through to a ship, after armor and shields.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct Damaged {
+    pub entity: Entity,
+    /// Where the ship was hit, or its center for damage without a point of
+    /// impact.
+    pub position: Vec2,
+    pub amount: i32,
+    pub kind: DamageKind,
+    /// The kind of the damaged ship, `None` for the player.
+    pub enemy: Option<EnemyKind>,
+    /// The player's weapon that dealt the damage.
+    pub weapon: Option<WeaponKind>,
+}
+
+/// A ship ran out of health. Enemies are despawned right after, the player is
+/// left in place.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct Destroyed {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub kind: Option<EnemyKind>,
+    /// The source of the final blow.
+    pub source: Option<Entity>,
+}
+
+pub fn apply_damage(
+    mut commands: Commands,
+    mut damage: MessageReader<DamageEvent>,
+    mut damaged: MessageWriter<Damaged>,
+    mut destroyed: MessageWriter<Destroyed>,
+    mut ships: Query<
+        (
+            &mut Health,
+            &Transform,
+            Option<&EnemyKind>,
+            Option<&Armored>,
+            Option<&FrontShield>,
+            Option<&mut HullSections>,
+        ),
+        Without<Emerging>,
+    >,
+    vampires: Query<&Vampiric>,
+    players: Query<(), With<Player>>,
+    mut events: MessageWriter<GameEvent>,
+) {
+    for event in damage.read() {
+        let source_position = event
+            .source
+            .filter(|source| *source != event.target)
+            .and_then(|source| ships.get(source).ok())
+            .map(|(_, transform, ..)| transform.translation.xy());
+        let Ok((mut health, transform, kind, armored, shield, sections)) =
+            ships.get_mut(event.target)
+        else {
+            continue;
+        };
+        // Already destroyed earlier this frame.
+        if health.current <= 0 {
+            continue;
+        }
+
+        let mut amount = event.amount;
+        if let Some(armored) = armored {
+            amount *= 1.0 - armored.reduction;
+        }
+        if let Some(shield) = shield
+            && let Some(source_position) = source_position
+            && let Some(to_source) = (source_position - transform.translation.xy()).try_normalize()
+            && to_source.angle_to(forward_vec(*transform)).abs() < shield.half_angle
+        {
+            amount *= 1.0 - shield.reduction;
+        }
+
+        let dealt = (amount.round() as i32).min(health.current);
+        health.current -= dealt;
+        // Fires and leaks don't come from any direction.
+        if let Some(mut sections) = sections
+            && let Some(source_position) = source_position
+        {
+            let section = HullSection::facing(
+                forward_vec(*transform),
+                source_position - transform.translation.xy(),
+            );
+            sections.damage(section, dealt as f32);
+        }
+        if dealt != 0 {
+            damaged.write(Damaged {
+                entity: event.target,
+                position: event.position.unwrap_or(transform.translation.xy()),
+                amount: dealt,
+                kind: event.kind,
+                enemy: kind.copied(),
+                weapon: event.weapon,
+            });
+        }
+        if dealt != 0 && players.contains(event.target) {
+            events.write(GameEvent::PlayerHealthChanged {
+                fraction: health.fraction(),
+            });
+        }
+        if health.current <= 0 {
+            destroyed.write(Destroyed {
+                entity: event.target,
+                position: transform.translation.xy(),
+                kind: kind.copied(),
+                source: event.source,
+            });
+            if !players.contains(event.target) {
+                commands.entity(event.target).despawn();
+            }
+        }
+
+        if let Some(source) = event.source
+            && let Ok(vampiric) = vampires.get(source)
+            && let Ok((mut health, ..)) = ships.get_mut(source)
+        {
+            let heal = (dealt as f32 * vampiric.life_steal).round() as i32;
+            health.current = (health.current + heal).min(health.max);
+        }
+    }
+}
@@ -1,13 +1,22 @@
 use avian2d::prelude::{AngularDamping, Collider, CollisionLayers, LinearDamping, Mass, RigidBody};
 use bevy::{
-    math::ops::atan2,
     prelude::*,
     render::render_resource::AsBindGroup,
     sprite_render::{Material2d, Material2dPlugin},
 };
+use serde::Serialize;
 
 use crate::{
-    demo::{GameCollisionLayer, Health, movement::MovementController, player::Player},
+    PausableSystems,
+    demo::{
+        GameCollisionLayer, Health,
+        abilities::Concealed,
+        hull::HullSections,
+        movement::MovementController,
+        player::Player,
+        steering::{Steering, apply_steering},
+        wave_director::WaveDirector,
+    },
     screens::Screen,
 };
 
@@ -15,16 +24,18 @@ pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(SpawnerConfig {
-            remaining_in_wave: 100,
-        })
-        .add_systems(Update, update_time.run_if(in_state(Screen::Gameplay)))
-        .add_systems(
-            Update,
-            (eval_spawners, remove_stuck_enemies, enemy_movement)
-                .run_if(in_state(Screen::Gameplay)),
-        )
-        .add_plugins(Material2dPlugin::<EnemyMaterial>::default());
+        app.add_systems(Update, update_time.run_if(in_state(Screen::Gameplay)))
+            .add_systems(
+                Update,
+                (
+                    remove_stuck_enemies,
+                    enemy_movement.before(apply_steering),
+                    emerge,
+                )
+                    .in_set(PausableSystems)
+                    .run_if(in_state(Screen::Gameplay)),
+            )
+            .add_plugins(Material2dPlugin::<EnemyMaterial>::default());
     }
 }
 fn update_time(
@@ -43,6 +54,23 @@ fn update_time(
 pub struct EnemyMaterial {
     #[uniform(0)]
     time: Vec4,
+    /// x: opacity
+    #[uniform(1)]
+    appearance: Vec4,
+    /// rgb: color of the sails, a: strength. Used to show affixes.
+    #[uniform(2)]
+    pub tint: Vec4,
+}
+
+impl Default for EnemyMaterial {
+    /// Starts out invisible, see [`Emerging`].
+    fn default() -> Self {
+        Self {
+            time: Vec4::ZERO,
+            appearance: Vec4::ZERO,
+            tint: Vec4::ZERO,
+        }
+    }
 }
 
 const ENEMY_SHADER_PATH: &str = "shaders/enemy.wesl";
@@ -66,72 +94,124 @@ impl Material2d for EnemyMaterial {
 }
 
 #[derive(Component)]
-pub struct Spawner {
-    pub timer: Timer,
+pub struct Enemy;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Reflect)]
+pub enum EnemyKind {
+    Raider,
+    FireShip,
+    Boss,
 }
 
-#[derive(Resource)]
-pub struct SpawnerConfig {
-    pub remaining_in_wave: u32,
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 3] = [EnemyKind::Raider, EnemyKind::FireShip, EnemyKind::Boss];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EnemyKind::Raider => "Raider",
+            EnemyKind::FireShip => "Fire Ship",
+            EnemyKind::Boss => "Boss",
+        }
+    }
+
+    /// Free water needed around a spawn point for this kind of ship.
+    pub fn clearance(self) -> f32 {
+        match self {
+            EnemyKind::Raider => 90.0,
+            EnemyKind::FireShip => 80.0,
+            EnemyKind::Boss => 220.0,
+        }
+    }
 }
 
+/// Enemies fade in after spawning and can't be damaged until they are fully visible.
 #[derive(Component)]
-pub struct Enemy;
+pub struct Emerging {
+    pub timer: Timer,
+}
+
+const EMERGE_SECS: f32 = 1.0;
+
+impl Default for Emerging {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(EMERGE_SECS, TimerMode::Once),
+        }
+    }
+}
 
+/// Enemies that barely moved for a while are considered stuck and get respawned.
 #[derive(Component)]
-struct PositionRecording {
+pub struct PositionRecording {
     timer: Timer,
     position: Vec3,
 }
 
-fn eval_spawners(
+impl PositionRecording {
+    pub fn new(transform: Transform) -> PositionRecording {
+        PositionRecording {
+            timer: Timer::from_seconds(10.0, TimerMode::Repeating),
+            position: transform.translation,
+        }
+    }
+}
+
+pub fn enemy_collision_layers() -> CollisionLayers {
+    CollisionLayers::new(
+        GameCollisionLayer::Enemy,
+        [GameCollisionLayer::Terrain, GameCollisionLayer::Player],
+    )
+}
+
+/// A regular enemy ship.
+pub fn enemy(
+    transform: Transform,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<EnemyMaterial>,
+) -> impl Bundle {
+    let mesh = meshes.add(Rectangle::new(100.0, 100.0));
+    let material = materials.add(EnemyMaterial::default());
+    (
+        Name::new("Raider"),
+        Enemy,
+        EnemyKind::Raider,
+        Mesh2d(mesh),
+        MeshMaterial2d(material),
+        transform,
+        (
+            Collider::rectangle(100.0, 100.0),
+            RigidBody::Dynamic,
+            Mass(10.0),
+            AngularDamping(2.0),
+            LinearDamping(0.2),
+            enemy_collision_layers(),
+        ),
+        MovementController {
+            max_speed: 300.0,
+            ..default()
+        },
+        PositionRecording::new(transform),
+        Health::new(100),
+        HullSections::new(100),
+        Steering::default(),
+        Emerging::default(),
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+fn emerge(
     time: Res<Time>,
     mut commands: Commands,
-    mut config: ResMut<SpawnerConfig>,
-    mut spawners: Query<(&mut Spawner, &Transform)>,
-    mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<EnemyMaterial>>,
+    mut enemies: Query<(Entity, &mut Emerging, &MeshMaterial2d<EnemyMaterial>)>,
 ) {
-    if config.remaining_in_wave > 0 {
-        for (mut spawner, transform) in &mut spawners {
-            if config.remaining_in_wave == 0 {
-                break;
-            }
-
-            spawner.timer.tick(time.delta());
-            let mesh = meshes.add(Rectangle::new(100.0, 100.0));
-            let material = materials.add(EnemyMaterial { time: Vec4::ZERO });
-            if spawner.timer.is_finished() {
-                let collision = CollisionLayers::new(
-                    GameCollisionLayer::Enemy,
-                    [GameCollisionLayer::Terrain, GameCollisionLayer::Player],
-                );
-                commands.spawn((
-                    Enemy,
-                    Mesh2d(mesh),
-                    MeshMaterial2d(material),
-                    *transform,
-                    Collider::rectangle(100.0, 100.0),
-                    RigidBody::Dynamic,
-                    MovementController {
-                        max_speed: 300.0,
-                        ..default()
-                    },
-                    Mass(10.0),
-                    AngularDamping(2.0),
-                    LinearDamping(0.2),
-                    collision,
-                    PositionRecording {
-                        timer: Timer::from_seconds(10.0, TimerMode::Repeating),
-                        position: transform.translation,
-                    },
-                    Health(100),
-                ));
-                config.remaining_in_wave -= 1;
-            }
-            if spawner.timer.is_finished() {
-                spawner.timer.reset();
-            }
+    for (entity, mut emerging, material) in &mut enemies {
+        emerging.timer.tick(time.delta());
+        if let Some(m) = materials.get_mut(material.0.id()) {
+            m.appearance.x = emerging.timer.fraction();
+        }
+        if emerging.timer.is_finished() {
+            commands.entity(entity).remove::<Emerging>();
         }
     }
 }
@@ -139,16 +219,16 @@ fn eval_spawners(
 fn remove_stuck_enemies(
     time: Res<Time>,
     mut commands: Commands,
-    mut config: ResMut<SpawnerConfig>,
-    mut enemies: Query<(Entity, &mut PositionRecording, &Transform), With<Enemy>>,
+    mut director: ResMut<WaveDirector>,
+    mut enemies: Query<(Entity, &EnemyKind, &mut PositionRecording, &Transform), With<Enemy>>,
 ) {
-    for (entity, mut record, transform) in &mut enemies {
+    for (entity, kind, mut record, transform) in &mut enemies {
         record.timer.tick(time.delta());
         if record.timer.is_finished() {
             record.timer.reset();
             if record.position.distance_squared(transform.translation) < 1000.0 {
                 commands.entity(entity).despawn();
-                config.remaining_in_wave += 1;
+                director.pending.push(*kind);
             } else {
                 record.position = transform.translation;
             }
@@ -157,23 +237,19 @@ fn remove_stuck_enemies(
 }
 
 fn enemy_movement(
-    player_position: Query<&Transform, With<Player>>,
-    mut enemies: Query<(&mut MovementController, &Transform), (Without<Player>, With<Enemy>)>,
+    player_position: Query<(&Transform, Has<Concealed>), With<Player>>,
+    mut enemies: Query<(&mut Steering, &Transform), (Without<Player>, With<Enemy>)>,
 ) -> std::result::Result<(), BevyError> {
-    let player_position = player_position.single()?.translation;
-    for (mut enemy_movement, transform) in &mut enemies {
-        let angle = transform.rotation.to_euler(EulerRot::XYZ).2 + std::f32::consts::FRAC_PI_2;
-        let forward = Vec2::new(angle.cos(), angle.sin());
-
-        let to_player = (player_position - transform.translation).normalize().xy();
-
-        enemy_movement.intent = 10.0;
-        let a = to_player;
-        let b = forward;
-        enemy_movement.rotation_intent = -atan2(a.x * b.y - a.y * b.x, a.x * b.x + a.y * b.y);
-        if enemy_movement.rotation_intent.is_nan() {
-            enemy_movement.rotation_intent = 0.0;
-        }
+    let (player_transform, concealed) = player_position.single()?;
+    // Without a target, enemies keep heading where they last saw the player.
+    if concealed {
+        return Ok(());
+    }
+    let player_position = player_transform.translation;
+    for (mut steering, transform) in &mut enemies {
+        steering.seek = (player_position - transform.translation)
+            .xy()
+            .normalize_or_zero();
     }
     Ok(())
 }
@@ -0,0 +1,82 @@
+//! Gameplay events that other systems, like relics, can react to.
+//!
+//! Systems that notice something interesting write a [`GameEvent`], instead of
+//! every interested system having to look for it on its own.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        damage::{Destroyed, apply_damage},
+        enemy::EnemyKind,
+        loot::PickupKind,
+        player::Player,
+        weapons::WeaponKind,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<GameEvent>();
+    app.add_systems(
+        Update,
+        publish_kills
+            .after(apply_damage)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Message, Debug, Clone, Copy)]
+pub enum GameEvent {
+    EnemyKilled {
+        position: Vec2,
+        kind: EnemyKind,
+        /// Whether the killing blow came from the player.
+        by_player: bool,
+    },
+    /// The health of the player went up or down.
+    PlayerHealthChanged {
+        fraction: f32,
+    },
+    /// The player picked up a relic.
+    RelicFound,
+    /// One of the player's weapons fired.
+    ShotFired {
+        weapon: WeaponKind,
+    },
+    /// A shot from one of the player's weapons hit a ship.
+    WeaponHit {
+        weapon: WeaponKind,
+    },
+    /// All enemies of a wave were sunk.
+    WaveCleared {
+        wave: u32,
+    },
+    GoldEarned {
+        amount: u32,
+    },
+    /// The player collected a pickup.
+    PickedUp {
+        kind: PickupKind,
+    },
+}
+
+fn publish_kills(
+    mut destroyed: MessageReader<Destroyed>,
+    mut events: MessageWriter<GameEvent>,
+    players: Query<(), With<Player>>,
+) {
+    for event in destroyed.read() {
+        let Some(kind) = event.kind else {
+            continue;
+        };
+        events.write(GameEvent::EnemyKilled {
+            position: event.position,
+            kind,
+            by_player: event.source.is_some_and(|source| players.contains(source)),
+        });
+    }
+}
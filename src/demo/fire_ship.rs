@@ -0,0 +1,331 @@
+//! Fire ships are kamikaze enemies. They pick up speed while chasing the
+//! player and blow up on contact or when they are sunk.
+//!
+//! The [`Explosion`] damages every ship in its radius, enemies included, and
+//! sets them on fire. Fire ships caught in it go up as well, so sinking one in
+//! the middle of a pack can clear the whole pack.
+
+use std::time::Duration;
+
+use avian2d::prelude::*;
+use bevy::{
+    platform::collections::HashSet,
+    prelude::*,
+    render::render_resource::AsBindGroup,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        GameCollisionLayer, Health,
+        camera::ScreenShake,
+        damage::{DamageEvent, DamageKind, Destroyed},
+        enemy::{
+            Emerging, Enemy, EnemyKind, EnemyMaterial, PositionRecording, enemy_collision_layers,
+        },
+        hull::HullSections,
+        movement::MovementController,
+        player::{Player, PlayerStats},
+        status_effects::{ApplyStatus, StatusEffect, StatusEffectKind},
+        steering::Steering,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(Material2dPlugin::<ExplosionMaterial>::default());
+    app.add_systems(
+        Update,
+        (
+            accelerate_fire_ships,
+            detonate_on_contact,
+            explode_fire_ships,
+            fade_explosions,
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const FIRE_SHIP_HEALTH: i32 = 40;
+const FIRE_SHIP_THRUST: f32 = 6.0;
+const FIRE_SHIP_MAX_THRUST: f32 = 30.0;
+const FIRE_SHIP_SPEED: f32 = 250.0;
+const FIRE_SHIP_MAX_SPEED: f32 = 650.0;
+/// Time until a fire ship reaches its full speed.
+const FIRE_SHIP_RAMP_SECS: f32 = 6.0;
+
+const EXPLOSION_RADIUS: f32 = 350.0;
+const EXPLOSION_DAMAGE: f32 = 120.0;
+const EXPLOSION_TRAUMA: f32 = 0.6;
+/// Share of the damage that is still dealt at the edge of the explosion.
+const EXPLOSION_EDGE_FALLOFF: f32 = 0.25;
+const EXPLOSION_VISUAL_SECS: f32 = 0.6;
+/// How far from the center of a ship caught in an explosion it is hit.
+const EXPLOSION_IMPACT_OFFSET: f32 = 40.0;
+
+const BURNING_SECS: f32 = 4.0;
+const BURNING_DPS: f32 = 8.0;
+
+#[derive(Component)]
+pub struct FireShip {
+    /// Runs from the spawn until the fire ship reaches its full speed.
+    ramp: Timer,
+}
+
+impl Default for FireShip {
+    fn default() -> Self {
+        Self {
+            ramp: Timer::from_seconds(FIRE_SHIP_RAMP_SECS, TimerMode::Once),
+        }
+    }
+}
+
+pub fn fire_ship(
+    transform: Transform,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<EnemyMaterial>,
+) -> impl Bundle {
+    let mesh = meshes.add(Rectangle::new(80.0, 80.0));
+    let material = materials.add(EnemyMaterial::default());
+    (
+        Name::new("Fire Ship"),
+        Enemy,
+        EnemyKind::FireShip,
+        FireShip::default(),
+        Mesh2d(mesh),
+        MeshMaterial2d(material),
+        transform,
+        (
+            Collider::rectangle(80.0, 80.0),
+            RigidBody::Dynamic,
+            Mass(6.0),
+            AngularDamping(2.0),
+            LinearDamping(0.2),
+            enemy_collision_layers(),
+        ),
+        MovementController {
+            max_speed: FIRE_SHIP_SPEED,
+            ..default()
+        },
+        PositionRecording::new(transform),
+        Health::new(FIRE_SHIP_HEALTH),
+        HullSections::new(FIRE_SHIP_HEALTH),
+        Steering {
+            thrust: FIRE_SHIP_THRUST,
+            // Fire ships don't care much about keeping formation.
+            separation_weight: 0.5,
+            alignment_weight: 0.0,
+            ..default()
+        },
+        Emerging::default(),
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+fn accelerate_fire_ships(
+    time: Res<Time>,
+    mut fire_ships: Query<
+        (&mut FireShip, &mut Steering, &mut MovementController),
+        Without<Emerging>,
+    >,
+) {
+    for (mut fire_ship, mut steering, mut controller) in &mut fire_ships {
+        fire_ship.ramp.tick(time.delta());
+        ramp_speed(fire_ship.ramp.fraction(), &mut steering, &mut controller);
+    }
+}
+
+/// Sets the thrust and speed of a fire ship `t` of the way to its full speed.
+/// Affixes scale on top through [`MovementController::affix_multiplier`].
+pub fn ramp_speed(t: f32, steering: &mut Steering, controller: &mut MovementController) {
+    steering.thrust = FIRE_SHIP_THRUST.lerp(FIRE_SHIP_MAX_THRUST, t);
+    controller.max_speed = FIRE_SHIP_SPEED.lerp(FIRE_SHIP_MAX_SPEED, t);
+}
+
+/// Fire ships ramming the player sink themselves, which sets off the explosion.
+fn detonate_on_contact(
+    mut collisions: MessageReader<CollisionStart>,
+    player: Single<Entity, With<Player>>,
+    fire_ships: Query<&Health, (With<FireShip>, Without<Emerging>)>,
+    mut damage: MessageWriter<DamageEvent>,
+) {
+    let player = *player;
+    for event in collisions.read() {
+        let fire_ship = match (event.body1, event.body2) {
+            (Some(a), Some(b)) if a == player => b,
+            (Some(a), Some(b)) if b == player => a,
+            _ => continue,
+        };
+        let Ok(health) = fire_ships.get(fire_ship) else {
+            continue;
+        };
+        damage.write(DamageEvent {
+            target: fire_ship,
+            amount: health.current as f32,
+            kind: DamageKind::Explosion,
+            source: Some(fire_ship),
+            position: None,
+            weapon: None,
+        });
+    }
+}
+
+/// The visual of an explosion. The damage is dealt when it is spawned.
+#[derive(Component)]
+pub struct Explosion {
+    timer: Timer,
+}
+
+/// Damage dealt at `distance` from the center of an explosion.
+pub fn explosion_falloff(damage: f32, distance: f32, radius: f32) -> f32 {
+    if distance > radius {
+        return 0.0;
+    }
+    damage * 1.0.lerp(EXPLOSION_EDGE_FALLOFF, distance / radius)
+}
+
+fn explode_fire_ships(
+    mut commands: Commands,
+    mut destroyed: MessageReader<Destroyed>,
+    mut damage: MessageWriter<DamageEvent>,
+    mut status: MessageWriter<ApplyStatus>,
+    mut shake: MessageWriter<ScreenShake>,
+    spatial_query: SpatialQuery,
+    colliders: Query<&ColliderOf>,
+    ships: Query<&Transform, With<Health>>,
+    players: Query<&PlayerStats>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ExplosionMaterial>>,
+) {
+    let filter =
+        SpatialQueryFilter::from_mask([GameCollisionLayer::Player, GameCollisionLayer::Enemy]);
+    let shape = Collider::circle(EXPLOSION_RADIUS);
+    for event in destroyed.read() {
+        if event.kind != Some(EnemyKind::FireShip) {
+            continue;
+        }
+
+        // Whoever sank the fire ship gets credited with the explosion, so
+        // chain reactions keep the bonus of the player who started them.
+        let multiplier = event
+            .source
+            .and_then(|source| players.get(source).ok())
+            .map_or(1.0, |stats| stats.explosion_damage_percentage);
+        shake.write(ScreenShake {
+            trauma: EXPLOSION_TRAUMA,
+            origin: Some(event.position),
+        });
+
+        // Ships built from several colliders are only hit once.
+        let hit: HashSet<Entity> = spatial_query
+            .shape_intersections(&shape, event.position, 0.0, &filter)
+            .into_iter()
+            .filter_map(|collider| colliders.get(collider).ok())
+            .map(|collider| collider.body)
+            .filter(|body| *body != event.entity)
+            .collect();
+
+        for target in hit {
+            let Ok(transform) = ships.get(target) else {
+                continue;
+            };
+            let position = transform.translation.xy();
+            let distance = position.distance(event.position);
+            damage.write(DamageEvent {
+                target,
+                amount: explosion_falloff(
+                    EXPLOSION_DAMAGE * multiplier,
+                    distance,
+                    EXPLOSION_RADIUS,
+                ),
+                kind: DamageKind::Explosion,
+                source: event.source,
+                // The side of the ship facing the blast.
+                position: Some(
+                    position
+                        + (event.position - position).clamp_length_max(EXPLOSION_IMPACT_OFFSET),
+                ),
+                weapon: None,
+            });
+            status.write(ApplyStatus {
+                target,
+                effect: StatusEffect::new(
+                    StatusEffectKind::Burning,
+                    Duration::from_secs_f32(BURNING_SECS),
+                    BURNING_DPS * multiplier,
+                    event.source,
+                ),
+            });
+        }
+
+        commands.spawn((
+            Name::new("Explosion"),
+            Explosion {
+                timer: Timer::from_seconds(EXPLOSION_VISUAL_SECS, TimerMode::Once),
+            },
+            Mesh2d(meshes.add(Rectangle::new(
+                EXPLOSION_RADIUS * 2.0,
+                EXPLOSION_RADIUS * 2.0,
+            ))),
+            MeshMaterial2d(materials.add(ExplosionMaterial::default())),
+            Transform::from_translation(event.position.extend(1.0)),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+}
+
+fn fade_explosions(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut explosions: Query<(Entity, &mut Explosion, &MeshMaterial2d<ExplosionMaterial>)>,
+    mut materials: ResMut<Assets<ExplosionMaterial>>,
+) {
+    for (entity, mut explosion, material) in &mut explosions {
+        explosion.timer.tick(time.delta());
+        if let Some(m) = materials.get_mut(material.0.id()) {
+            m.time = Vec4::new(time.elapsed_secs(), explosion.timer.fraction(), 0.0, 0.0);
+        }
+        if explosion.timer.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone, Default)]
+pub struct ExplosionMaterial {
+    /// x: time, y: progress of the explosion 0..1
+    #[uniform(0)]
+    time: Vec4,
+}
+
+const EXPLOSION_SHADER_PATH: &str = "shaders/explosion.wesl";
+
+impl Material2d for ExplosionMaterial {
+    fn vertex_shader() -> bevy::shader::ShaderRef {
+        bevy::shader::ShaderRef::Default
+    }
+
+    fn fragment_shader() -> bevy::shader::ShaderRef {
+        EXPLOSION_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> bevy::sprite_render::AlphaMode2d {
+        bevy::sprite_render::AlphaMode2d::Blend
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::fire_ship::explosion_falloff;
+
+    #[test]
+    fn falloff() {
+        assert_eq!(explosion_falloff(100.0, 0.0, 200.0), 100.0);
+        assert_eq!(explosion_falloff(100.0, 200.0, 200.0), 25.0);
+        assert_eq!(explosion_falloff(100.0, 201.0, 200.0), 0.0);
+        assert!(explosion_falloff(100.0, 100.0, 200.0) < 100.0);
+    }
+}
@@ -0,0 +1,211 @@
+//! The best runs, kept on disk between sessions.
+//!
+//! Every finished run is scored and compared to the best run with the same
+//! mode, ship and seed. The list lives in the platform's config directory; if
+//! it is missing or can't be read, the game starts with an empty list instead.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    demo::{run_stats::RunStats, ships::HullKind},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(HighScores::load());
+    app.init_resource::<LastRun>();
+    app.add_systems(OnEnter(Screen::GameOver), record_run);
+}
+
+const SCORE_PER_WAVE: u32 = 1000;
+const SCORE_PER_KILL: u32 = 50;
+const SCORE_PER_SECOND: u32 = 1;
+/// Only this many records are kept, the lowest scores are dropped first.
+const MAX_RECORDS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    /// Surviving wave after wave, the only mode so far.
+    #[default]
+    Waves,
+}
+
+impl GameMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Waves => "Waves",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub mode: GameMode,
+    pub hull: HullKind,
+    pub seed: u32,
+    pub score: u32,
+    pub waves: u32,
+    pub kills: u32,
+    /// Seconds survived.
+    pub time: f32,
+}
+
+impl HighScore {
+    pub fn new(mode: GameMode, stats: &RunStats) -> HighScore {
+        let waves = stats.waves_cleared;
+        let kills = stats.total_kills();
+        let time = stats.time_survived.as_secs_f32();
+        HighScore {
+            mode,
+            hull: stats.hull,
+            seed: stats.seed,
+            score: score(waves, kills, time),
+            waves,
+            kills,
+            time,
+        }
+    }
+
+    fn same_key(&self, other: &HighScore) -> bool {
+        self.mode == other.mode && self.hull == other.hull && self.seed == other.seed
+    }
+}
+
+pub fn score(waves: u32, kills: u32, secs: f32) -> u32 {
+    waves * SCORE_PER_WAVE + kills * SCORE_PER_KILL + secs as u32 * SCORE_PER_SECOND
+}
+
+/// The best run for each mode, ship and seed, highest score first.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub records: Vec<HighScore>,
+}
+
+impl HighScores {
+    fn path() -> Option<PathBuf> {
+        Some(
+            dirs::config_dir()?
+                .join(env!("CARGO_PKG_NAME"))
+                .join("high_scores.json"),
+        )
+    }
+
+    pub fn load() -> HighScores {
+        let Some(path) = HighScores::path() else {
+            return HighScores::default();
+        };
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            return HighScores::default();
+        };
+        match HighScores::from_json(&json) {
+            Ok(high_scores) => high_scores,
+            Err(err) => {
+                warn!("Ignoring unreadable high scores {}: {err}", path.display());
+                HighScores::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = HighScores::path() else {
+            return;
+        };
+        let save = || -> Result<(), Box<dyn std::error::Error>> {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+            Ok(())
+        };
+        if let Err(err) = save() {
+            warn!(
+                "Could not save the high scores to {}: {err}",
+                path.display()
+            );
+        }
+    }
+
+    fn from_json(json: &str) -> serde_json::Result<HighScores> {
+        let mut high_scores: HighScores = serde_json::from_str(json)?;
+        high_scores.sort();
+        Ok(high_scores)
+    }
+
+    /// Adds the run if it beats the record for its mode, ship and seed, and
+    /// returns whether it did.
+    pub fn submit(&mut self, run: HighScore) -> bool {
+        match self.records.iter_mut().find(|record| record.same_key(&run)) {
+            Some(record) if record.score >= run.score => return false,
+            Some(record) => *record = run,
+            None => self.records.push(run),
+        }
+        self.sort();
+        self.records.contains(&run)
+    }
+
+    fn sort(&mut self) {
+        self.records
+            .sort_by(|a, b| b.score.cmp(&a.score).then(a.seed.cmp(&b.seed)));
+        self.records.truncate(MAX_RECORDS);
+    }
+}
+
+/// How the run that just ended compares to the records.
+#[derive(Resource, Debug, Default)]
+pub struct LastRun {
+    pub score: u32,
+    pub new_record: bool,
+}
+
+pub fn record_run(
+    stats: Res<RunStats>,
+    mut high_scores: ResMut<HighScores>,
+    mut last_run: ResMut<LastRun>,
+) {
+    let run = HighScore::new(GameMode::Waves, &stats);
+    let new_record = high_scores.submit(run);
+    if new_record {
+        high_scores.save();
+    }
+    *last_run = LastRun {
+        score: run.score,
+        new_record,
+    };
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::{
+        high_scores::{GameMode, HighScore, HighScores, score},
+        ships::HullKind,
+    };
+
+    #[test]
+    fn records_are_kept_per_key() {
+        assert_eq!(score(2, 10, 90.5), 2590);
+
+        let run = |hull, seed, score| HighScore {
+            mode: GameMode::Waves,
+            hull,
+            seed,
+            score,
+            waves: 0,
+            kills: 0,
+            time: 0.0,
+        };
+        let mut high_scores = HighScores::default();
+        assert!(high_scores.submit(run(HullKind::Brig, 1, 100)));
+        assert!(!high_scores.submit(run(HullKind::Brig, 1, 100)));
+        assert!(high_scores.submit(run(HullKind::Sloop, 1, 50)));
+        assert!(high_scores.submit(run(HullKind::Brig, 1, 300)));
+        assert_eq!(high_scores.records.len(), 2);
+        assert_eq!(high_scores.records[0].score, 300);
+
+        let saved = serde_json::to_string(&high_scores).unwrap();
+        assert_eq!(HighScores::from_json(&saved).unwrap().records.len(), 2);
+        assert!(HighScores::from_json("{ not json").is_err());
+    }
+}
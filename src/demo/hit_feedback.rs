@@ -0,0 +1,260 @@
+//! Feedback for hits.
+//!
+//! Every [`Damaged`] message pops a floating number where the ship was hit,
+//! colored by the kind of damage. The numbers are pooled, since hundreds of
+//! burning enemies produce a lot of them.
+//! Enemies also get a health bar that shows up when they are hit and fades
+//! out again after a few seconds without damage.
+
+use bevy::{prelude::*, sprite::Anchor};
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        Health,
+        damage::{DamageKind, Damaged, apply_damage},
+        enemy::{Enemy, EnemyKind},
+    },
+    screens::Screen,
+    theme::palette::{BAR_BACKGROUND, HEALTH_BAR},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DamageNumberPool>();
+    app.add_observer(add_health_bar);
+    app.add_systems(OnEnter(Screen::Gameplay), reset_pool);
+    app.add_systems(
+        Update,
+        (
+            (spawn_damage_numbers, float_damage_numbers).chain(),
+            (show_health_bars, update_health_bars).chain(),
+        )
+            .after(apply_damage)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Never more numbers than this on screen at once, further hits are not shown.
+const MAX_DAMAGE_NUMBERS: usize = 200;
+const NUMBER_SECS: f32 = 0.8;
+const NUMBER_RISE_SPEED: f32 = 90.0;
+const NUMBER_SPREAD: f32 = 30.0;
+const NUMBER_FONT_SIZE: f32 = 28.0;
+const FEEDBACK_Z: f32 = 10.0;
+
+const BAR_SIZE: Vec2 = Vec2::new(90.0, 10.0);
+/// Health bars stay fully visible this long after a hit...
+const BAR_HOLD_SECS: f32 = 3.0;
+/// ...and then fade out over this long.
+const BAR_FADE_SECS: f32 = 1.0;
+
+fn damage_color(kind: DamageKind) -> Color {
+    match kind {
+        DamageKind::Cannon => Color::srgb(0.95, 0.95, 0.95),
+        DamageKind::Explosion => Color::srgb(1.0, 0.45, 0.2),
+        DamageKind::Burning => Color::srgb(1.0, 0.6, 0.1),
+        DamageKind::Ram => Color::srgb(0.8, 0.6, 0.4),
+        DamageKind::Drain => Color::srgb(0.7, 0.35, 0.9),
+        DamageKind::Leaking => Color::srgb(0.35, 0.65, 1.0),
+    }
+}
+
+/// Damage numbers that finished floating, ready to be shown again.
+#[derive(Resource, Debug, Default)]
+struct DamageNumberPool {
+    free: Vec<Entity>,
+    /// Numbers spawned so far, free or not.
+    spawned: usize,
+}
+
+#[derive(Component, Debug)]
+struct DamageNumber {
+    lifetime: Timer,
+}
+
+fn reset_pool(mut pool: ResMut<DamageNumberPool>) {
+    // The numbers themselves are despawned when leaving the gameplay screen.
+    *pool = DamageNumberPool::default();
+}
+
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    mut damaged: MessageReader<Damaged>,
+    mut pool: ResMut<DamageNumberPool>,
+    mut numbers: Query<(
+        &mut DamageNumber,
+        &mut Text2d,
+        &mut TextColor,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    let mut rng = rand::rng();
+    for event in damaged.read() {
+        let position = event.position
+            + Vec2::new(
+                rng.random_range(-NUMBER_SPREAD..NUMBER_SPREAD),
+                rng.random_range(-NUMBER_SPREAD..NUMBER_SPREAD),
+            );
+        let text = event.amount.to_string();
+        let color = damage_color(event.kind);
+
+        if let Some(entity) = pool.free.pop()
+            && let Ok((mut number, mut text2d, mut text_color, mut transform, mut visibility)) =
+                numbers.get_mut(entity)
+        {
+            number.lifetime.reset();
+            text2d.0 = text;
+            text_color.0 = color;
+            transform.translation = position.extend(FEEDBACK_Z);
+            *visibility = Visibility::Inherited;
+        } else if pool.spawned < MAX_DAMAGE_NUMBERS {
+            pool.spawned += 1;
+            commands.spawn((
+                Name::new("Damage Number"),
+                DamageNumber {
+                    lifetime: Timer::from_seconds(NUMBER_SECS, TimerMode::Once),
+                },
+                Text2d::new(text),
+                TextFont::from_font_size(NUMBER_FONT_SIZE),
+                TextColor(color),
+                Transform::from_translation(position.extend(FEEDBACK_Z)),
+                Visibility::Inherited,
+                DespawnOnExit(Screen::Gameplay),
+            ));
+        }
+    }
+}
+
+fn float_damage_numbers(
+    time: Res<Time>,
+    mut pool: ResMut<DamageNumberPool>,
+    mut numbers: Query<(
+        Entity,
+        &mut DamageNumber,
+        &mut TextColor,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    for (entity, mut number, mut color, mut transform, mut visibility) in &mut numbers {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        number.lifetime.tick(time.delta());
+        if number.lifetime.is_finished() {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+        transform.translation.y += NUMBER_RISE_SPEED * time.delta_secs();
+        color.0.set_alpha(1.0 - number.lifetime.fraction().powi(2));
+    }
+}
+
+#[derive(Component, Debug)]
+struct HealthBar {
+    target: Entity,
+    /// Distance of the bar above the center of the ship.
+    offset: f32,
+    since_hit: Timer,
+}
+
+#[derive(Component, Debug)]
+struct HealthBarFill;
+
+/// The health bar of an enemy.
+#[derive(Component, Debug)]
+struct HasHealthBar(Entity);
+
+/// Every enemy gets a health bar, which stays hidden until it is hit.
+fn add_health_bar(add: On<Add, Enemy>, kinds: Query<&EnemyKind>, mut commands: Commands) {
+    let offset = kinds
+        .get(add.entity)
+        .map(|kind| kind.clearance() + BAR_SIZE.y)
+        .unwrap_or(100.0);
+    let mut since_hit = Timer::from_seconds(BAR_HOLD_SECS + BAR_FADE_SECS, TimerMode::Once);
+    since_hit.tick(since_hit.duration());
+
+    let bar = commands
+        .spawn((
+            Name::new("Health Bar"),
+            HealthBar {
+                target: add.entity,
+                offset,
+                since_hit,
+            },
+            Sprite::from_color(BAR_BACKGROUND, BAR_SIZE),
+            Transform::from_xyz(0.0, 0.0, FEEDBACK_Z),
+            Visibility::Hidden,
+            DespawnOnExit(Screen::Gameplay),
+            children![(
+                Name::new("Health Bar Fill"),
+                HealthBarFill,
+                Sprite::from_color(HEALTH_BAR, BAR_SIZE),
+                Anchor::CENTER_LEFT,
+                Transform::from_xyz(-BAR_SIZE.x / 2.0, 0.0, 0.1),
+            )],
+        ))
+        .id();
+    commands.entity(add.entity).insert(HasHealthBar(bar));
+}
+
+fn show_health_bars(
+    mut damaged: MessageReader<Damaged>,
+    enemies: Query<&HasHealthBar>,
+    mut bars: Query<&mut HealthBar>,
+) {
+    for event in damaged.read() {
+        if let Ok(HasHealthBar(bar)) = enemies.get(event.entity)
+            && let Ok(mut bar) = bars.get_mut(*bar)
+        {
+            bar.since_hit.reset();
+        }
+    }
+}
+
+fn update_health_bars(
+    mut commands: Commands,
+    time: Res<Time>,
+    targets: Query<(&Transform, &Health), (Without<HealthBar>, Without<HealthBarFill>)>,
+    mut bars: Query<
+        (
+            Entity,
+            &mut HealthBar,
+            &mut Transform,
+            &mut Sprite,
+            &mut Visibility,
+            &Children,
+        ),
+        Without<HealthBarFill>,
+    >,
+    mut fills: Query<(&mut Transform, &mut Sprite), With<HealthBarFill>>,
+) {
+    for (entity, mut bar, mut transform, mut sprite, mut visibility, children) in &mut bars {
+        let Ok((target, health)) = targets.get(bar.target) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        bar.since_hit.tick(time.delta());
+        if bar.since_hit.is_finished() {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let remaining = bar.since_hit.duration().as_secs_f32() - bar.since_hit.elapsed_secs();
+        let alpha = (remaining / BAR_FADE_SECS).min(1.0);
+        transform.translation = (target.translation.xy() + Vec2::Y * bar.offset).extend(FEEDBACK_Z);
+        sprite.color = BAR_BACKGROUND.with_alpha(BAR_BACKGROUND.alpha() * alpha);
+        for child in children {
+            if let Ok((mut fill_transform, mut fill_sprite)) = fills.get_mut(*child) {
+                fill_transform.scale.x = health.fraction().max(0.0);
+                fill_sprite.color = HEALTH_BAR.with_alpha(alpha);
+            }
+        }
+    }
+}
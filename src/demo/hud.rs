@@ -0,0 +1,241 @@
+//! The in-game HUD.
+//!
+//! Shows the state of the player's ship and the run: hull, wave, elapsed time,
+//! gold and XP, how much of the world was explored, and whether each weapon is
+//! loaded. Abilities and the surf meter show their own widgets, see the
+//! `abilities` and `surfing` modules.
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*, time::Stopwatch};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        Health,
+        enemy::Enemy,
+        loot::PlayerProgress,
+        player::Player,
+        terrain::fog::Exploration,
+        upgrades::xp_to_next_level,
+        wave_director::WaveDirector,
+        weapons::{WeaponSlots, WeaponType},
+    },
+    screens::Screen,
+    theme::{
+        palette::{BAR_BACKGROUND, HEALTH_BAR, WEAPON_READY, XP_BAR},
+        prelude::*,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunTime>();
+    app.add_systems(OnEnter(Screen::Gameplay), (reset_run_time, spawn_hud));
+    app.add_systems(
+        Update,
+        (
+            tick_run_time,
+            update_hull_bar,
+            update_wave_label,
+            update_time_label,
+            update_progress,
+            update_exploration_label,
+            update_weapon_pips,
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Time spent in the current run, not counting pauses.
+#[derive(Resource, Debug, Default)]
+pub struct RunTime(pub Stopwatch);
+
+fn reset_run_time(mut time: ResMut<RunTime>) {
+    time.0.reset();
+}
+
+fn tick_run_time(time: Res<Time>, mut run_time: ResMut<RunTime>) {
+    run_time.0.tick(time.delta());
+}
+
+#[derive(Component)]
+struct HullBar;
+
+#[derive(Component)]
+struct HullLabel;
+
+#[derive(Component)]
+struct WaveLabel;
+
+#[derive(Component)]
+struct TimeLabel;
+
+#[derive(Component)]
+struct GoldLabel;
+
+#[derive(Component)]
+struct ExplorationLabel;
+
+#[derive(Component)]
+struct XpBar;
+
+#[derive(Component)]
+struct LevelLabel;
+
+/// The slot of [`WeaponSlots`] a pip shows.
+#[derive(Component, Debug, Clone, Copy)]
+enum WeaponPip {
+    Left(usize),
+    Front,
+    Right(usize),
+}
+
+impl WeaponPip {
+    fn weapon(self, slots: &WeaponSlots) -> Option<&WeaponType> {
+        match self {
+            WeaponPip::Left(i) => slots.left[i].as_ref(),
+            WeaponPip::Front => slots.front.as_ref(),
+            WeaponPip::Right(i) => slots.right[i].as_ref(),
+        }
+    }
+}
+
+const PIP_SIZE: f32 = 16.0;
+
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("HUD"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(16),
+            left: px(16),
+            flex_direction: FlexDirection::Column,
+            row_gap: px(6),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (widget::label("Hull"), HullLabel),
+            (widget::bar("Hull", px(300), px(16), HEALTH_BAR), HullBar),
+            (widget::label("Wave 1"), WaveLabel),
+            (widget::label("00:00"), TimeLabel),
+            (widget::label("Gold 0"), GoldLabel),
+            (widget::label("Explored 0%"), ExplorationLabel),
+            (
+                Name::new("Weapon Pips"),
+                Node {
+                    column_gap: px(4),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                Children::spawn(SpawnIter(
+                    [
+                        WeaponPip::Left(2),
+                        WeaponPip::Left(1),
+                        WeaponPip::Left(0),
+                        WeaponPip::Front,
+                        WeaponPip::Right(0),
+                        WeaponPip::Right(1),
+                        WeaponPip::Right(2),
+                    ]
+                    .into_iter()
+                    .map(|pip| (
+                        widget::icon("Weapon Pip", px(PIP_SIZE), BAR_BACKGROUND),
+                        pip
+                    )),
+                )),
+            ),
+        ],
+    ));
+    commands.spawn((
+        Name::new("XP Bar"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(16),
+            width: percent(100),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: px(4),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (widget::label("Level 1"), LevelLabel),
+            (widget::bar("XP", px(500), px(12), XP_BAR), XpBar),
+        ],
+    ));
+}
+
+fn update_hull_bar(
+    health: Single<&Health, (With<Player>, Changed<Health>)>,
+    mut bar: Single<&mut Bar, With<HullBar>>,
+    mut label: Single<&mut Text, With<HullLabel>>,
+) {
+    bar.0 = health.fraction();
+    label.0 = format!("Hull {}/{}", health.current.max(0), health.max);
+}
+
+fn update_wave_label(
+    director: Res<WaveDirector>,
+    enemies: Query<(), With<Enemy>>,
+    mut label: Single<&mut Text, With<WaveLabel>>,
+) {
+    let remaining = director.pending.len() + enemies.iter().count();
+    let text = if remaining == 0 {
+        format!("Wave {} cleared", director.wave)
+    } else {
+        format!("Wave {}: {remaining} enemies left", director.wave)
+    };
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+fn update_time_label(run_time: Res<RunTime>, mut label: Single<&mut Text, With<TimeLabel>>) {
+    let secs = run_time.0.elapsed().as_secs();
+    let text = format!("{:02}:{:02}", secs / 60, secs % 60);
+    if label.0 != text {
+        label.0 = text;
+    }
+}
+
+fn update_progress(
+    progress: Res<PlayerProgress>,
+    mut xp_bar: Single<&mut Bar, With<XpBar>>,
+    mut gold: Single<&mut Text, (With<GoldLabel>, Without<LevelLabel>)>,
+    mut level: Single<&mut Text, (With<LevelLabel>, Without<GoldLabel>)>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+    xp_bar.0 = progress.xp as f32 / xp_to_next_level(progress.level) as f32;
+    gold.0 = format!("Gold {}", progress.gold);
+    level.0 = format!("Level {}", progress.level);
+}
+
+fn update_exploration_label(
+    exploration: Res<Exploration>,
+    mut label: Single<&mut Text, With<ExplorationLabel>>,
+) {
+    if exploration.is_changed() {
+        label.0 = format!("Explored {:.0}%", exploration.fraction * 100.0);
+    }
+}
+
+/// Pips light up while their weapon reloads and are fully lit once it is loaded.
+fn update_weapon_pips(
+    slots: Single<&WeaponSlots, With<Player>>,
+    mut pips: Query<(&WeaponPip, &mut BackgroundColor)>,
+) {
+    for (pip, mut color) in &mut pips {
+        let new = match pip.weapon(&slots) {
+            Some(weapon) => WEAPON_READY.with_alpha(0.2 + 0.8 * weapon.reload_fraction()),
+            None => BAR_BACKGROUND,
+        };
+        if color.0 != new {
+            color.0 = new;
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Hull sections of a ship.
+//!
+//! Besides its overall [`Health`], a ship with [`HullSections`] tracks the
+//! integrity of its bow, stern, port and starboard side. Damage goes to the
+//! section facing the ship that dealt it. A destroyed side can't fire its
+//! weapons any more, and badly damaged sections let water in, which shows up
+//! as [`StatusEffectKind::Leaking`]. Sections that haven't been hit for a
+//! while are slowly patched up again, and the player's ship is repaired
+//! quickly while docked at a [`Port`].
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        Health,
+        damage::apply_damage,
+        events::GameEvent,
+        player::Player,
+        status_effects::{ApplyStatus, StatusEffect, StatusEffectKind, StatusEffects},
+        terrain::ports::{DOCK_RADIUS, Port},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (leak, repair_over_time, repair_at_ports)
+            .after(apply_damage)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Cosine between heading and hit direction above which the bow or stern is hit.
+const END_ALIGNMENT: f32 = 0.7;
+/// Sections below this share of their integrity are leaking.
+const LEAK_THRESHOLD: f32 = 0.4;
+const LEAK_TICK_SECS: f32 = 2.0;
+const LEAK_DPS: f32 = 2.0;
+/// Sections start to be repaired after not being hit for this long.
+const REPAIR_DELAY_SECS: f32 = 6.0;
+const REPAIR_PER_SEC: f32 = 1.5;
+const PORT_REPAIR_PER_SEC: f32 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum HullSection {
+    Bow,
+    Stern,
+    /// The left side, looking towards the bow.
+    Port,
+    /// The right side, looking towards the bow.
+    Starboard,
+}
+
+impl HullSection {
+    pub const ALL: [HullSection; 4] = [
+        HullSection::Bow,
+        HullSection::Stern,
+        HullSection::Port,
+        HullSection::Starboard,
+    ];
+
+    /// The section of a ship heading along `forward` that faces `towards`.
+    pub fn facing(forward: Vec2, towards: Vec2) -> HullSection {
+        let towards = towards.normalize_or_zero();
+        let alignment = forward.dot(towards);
+        if alignment > END_ALIGNMENT {
+            HullSection::Bow
+        } else if alignment < -END_ALIGNMENT {
+            HullSection::Stern
+        } else if forward.perp().dot(towards) > 0.0 {
+            HullSection::Port
+        } else {
+            HullSection::Starboard
+        }
+    }
+
+    /// Share of the health of the ship in this section.
+    fn share(self) -> f32 {
+        match self {
+            HullSection::Bow | HullSection::Stern => 0.2,
+            HullSection::Port | HullSection::Starboard => 0.3,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            HullSection::Bow => 0,
+            HullSection::Stern => 1,
+            HullSection::Port => 2,
+            HullSection::Starboard => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SectionIntegrity {
+    current: f32,
+    max: f32,
+    since_hit: Timer,
+}
+
+/// The integrity of each [`HullSection`] of a ship.
+#[derive(Component, Debug, Clone)]
+pub struct HullSections {
+    sections: [SectionIntegrity; 4],
+    leak_tick: Timer,
+    /// Repairs that don't add up to a whole point of health yet.
+    pending_heal: f32,
+}
+
+impl HullSections {
+    /// Splits the maximum health of a ship over its sections.
+    pub fn new(max_health: i32) -> HullSections {
+        HullSections {
+            sections: HullSection::ALL.map(|section| {
+                let max = max_health as f32 * section.share();
+                let mut since_hit = Timer::from_seconds(REPAIR_DELAY_SECS, TimerMode::Once);
+                since_hit.tick(since_hit.duration());
+                SectionIntegrity {
+                    current: max,
+                    max,
+                    since_hit,
+                }
+            }),
+            leak_tick: Timer::from_seconds(LEAK_TICK_SECS, TimerMode::Repeating),
+            pending_heal: 0.0,
+        }
+    }
+
+    pub fn fraction(&self, section: HullSection) -> f32 {
+        let integrity = &self.sections[section.index()];
+        integrity.current / integrity.max
+    }
+
+    pub fn is_destroyed(&self, section: HullSection) -> bool {
+        self.sections[section.index()].current <= 0.0
+    }
+
+    pub fn damage(&mut self, section: HullSection, amount: f32) {
+        let integrity = &mut self.sections[section.index()];
+        integrity.current = (integrity.current - amount).max(0.0);
+        integrity.since_hit.reset();
+    }
+
+    /// Patches up the sections, most damaged first. Returns the integrity that
+    /// was actually restored.
+    pub fn repair(&mut self, mut amount: f32) -> f32 {
+        let mut repaired = 0.0;
+        let mut order = HullSection::ALL;
+        order.sort_by(|a, b| self.fraction(*a).total_cmp(&self.fraction(*b)));
+        for section in order {
+            let integrity = &mut self.sections[section.index()];
+            let patch = (integrity.max - integrity.current).min(amount);
+            integrity.current += patch;
+            amount -= patch;
+            repaired += patch;
+        }
+        repaired
+    }
+
+    /// Turns repaired integrity into health. Health only comes back in whole
+    /// points, the rest is kept for later. Returns whether the health changed.
+    fn restore_health(&mut self, repaired: f32, health: &mut Health) -> bool {
+        self.pending_heal += repaired;
+        let heal = self.pending_heal.floor();
+        self.pending_heal -= heal;
+        if heal <= 0.0 || health.current >= health.max {
+            return false;
+        }
+        health.current = (health.current + heal as i32).min(health.max);
+        true
+    }
+
+    fn leaking(&self) -> usize {
+        HullSection::ALL
+            .iter()
+            .filter(|section| self.fraction(**section) < LEAK_THRESHOLD)
+            .count()
+    }
+}
+
+/// Every badly damaged section adds a stack of leaking. The stacks are set
+/// rather than added, so they follow the sections as they are holed and patched.
+fn leak(
+    time: Res<Time>,
+    mut ships: Query<(Entity, &mut HullSections, Option<&mut StatusEffects>)>,
+    mut status: MessageWriter<ApplyStatus>,
+) {
+    for (entity, mut sections, effects) in &mut ships {
+        sections.leak_tick.tick(time.delta());
+        if !sections.leak_tick.just_finished() {
+            continue;
+        }
+        let leaking = sections.leaking() as u32;
+        if leaking == 0 {
+            continue;
+        }
+        let mut effect = StatusEffect::new(
+            StatusEffectKind::Leaking,
+            Duration::from_secs_f32(LEAK_TICK_SECS * 1.5),
+            LEAK_DPS,
+            None,
+        );
+        effect.stacks = leaking;
+        if let Some(mut effects) = effects
+            && let Some(active) = effects.get_mut(StatusEffectKind::Leaking)
+        {
+            active.stacks = leaking;
+            active.remaining = effect.remaining;
+        } else {
+            status.write(ApplyStatus {
+                target: entity,
+                effect,
+            });
+        }
+    }
+}
+
+fn repair_over_time(
+    time: Res<Time>,
+    mut ships: Query<(&mut HullSections, &mut Health, Has<Player>)>,
+    mut events: MessageWriter<GameEvent>,
+) {
+    for (mut sections, mut health, is_player) in &mut ships {
+        if health.current <= 0 {
+            continue;
+        }
+        let mut repaired = 0.0;
+        for integrity in &mut sections.sections {
+            integrity.since_hit.tick(time.delta());
+            if integrity.since_hit.is_finished() && integrity.current < integrity.max {
+                let patch =
+                    (REPAIR_PER_SEC * time.delta_secs()).min(integrity.max - integrity.current);
+                integrity.current += patch;
+                repaired += patch;
+            }
+        }
+        if sections.restore_health(repaired, &mut health) && is_player {
+            events.write(GameEvent::PlayerHealthChanged {
+                fraction: health.fraction(),
+            });
+        }
+    }
+}
+
+/// Docked ships are repaired right away, even sections that were just hit.
+fn repair_at_ports(
+    time: Res<Time>,
+    ports: Query<&Transform, With<Port>>,
+    player: Single<(&Transform, &mut HullSections, &mut Health), With<Player>>,
+    mut events: MessageWriter<GameEvent>,
+) {
+    let (transform, mut sections, mut health) = player.into_inner();
+    let position = transform.translation.xy();
+    if health.current <= 0
+        || !ports
+            .iter()
+            .any(|port| port.translation.xy().distance(position) < DOCK_RADIUS)
+    {
+        return;
+    }
+    let amount = PORT_REPAIR_PER_SEC * time.delta_secs();
+    sections.repair(amount);
+    if sections.restore_health(amount, &mut health) {
+        events.write(GameEvent::PlayerHealthChanged {
+            fraction: health.fraction(),
+        });
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::math::Vec2;
+
+    #[allow(unused)]
+    use crate::demo::{
+        Health,
+        hull::{HullSection, HullSections},
+    };
+
+    #[test]
+    fn sections_take_and_repair_damage() {
+        assert_eq!(HullSection::facing(Vec2::Y, Vec2::Y), HullSection::Bow);
+        assert_eq!(HullSection::facing(Vec2::Y, Vec2::NEG_X), HullSection::Port);
+        assert_eq!(
+            HullSection::facing(Vec2::Y, Vec2::X),
+            HullSection::Starboard
+        );
+        assert_eq!(
+            HullSection::facing(Vec2::Y, Vec2::NEG_Y),
+            HullSection::Stern
+        );
+
+        let mut sections = HullSections::new(1000);
+        sections.damage(HullSection::Port, 500.0);
+        sections.damage(HullSection::Bow, 50.0);
+        assert!(sections.is_destroyed(HullSection::Port));
+        assert_eq!(sections.leaking(), 1);
+
+        assert_eq!(sections.repair(320.0), 320.0);
+        assert_eq!(sections.fraction(HullSection::Port), 1.0);
+        assert_eq!(sections.fraction(HullSection::Bow), 0.85);
+        assert_eq!(sections.repair(100.0), 30.0);
+
+        let mut health = Health::new(1000);
+        health.current = 990;
+        assert!(!sections.restore_health(0.5, &mut health));
+        assert!(sections.restore_health(0.5, &mut health));
+        assert_eq!(health.current, 991);
+    }
+}
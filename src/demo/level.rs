@@ -5,7 +5,10 @@ use bevy::prelude::*;
 use crate::{
     asset_tracking::LoadResource,
     audio::music,
-    demo::player::{BoatMaterial, player},
+    demo::{
+        player::{BoatMaterial, player},
+        ships::SelectedHull,
+    },
     screens::Screen,
 };
 
@@ -33,6 +36,7 @@ impl FromWorld for LevelAssets {
 pub fn spawn_level(
     mut commands: Commands,
     level_assets: Res<LevelAssets>,
+    selected_hull: Res<SelectedHull>,
     mut materials: ResMut<Assets<BoatMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
 ) {
@@ -42,7 +46,7 @@ pub fn spawn_level(
         Visibility::default(),
         DespawnOnExit(Screen::Gameplay),
         children![
-            player(400.0, &mut meshes, &mut materials),
+            player(selected_hull.0.hull(), &mut meshes, &mut materials),
             (
                 Name::new("Gameplay Music"),
                 music(level_assets.music.clone())
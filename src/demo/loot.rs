@@ -0,0 +1,324 @@
+//! Enemies sunk by the player drop gold and XP.
+//!
+//! What a ship drops is rolled from the [`LootTable`] of its [`EnemyKind`]. The
+//! [`Pickup`]s float on the water, drifting with the [`Waves`], until they are
+//! pulled in by the player or sink after a while.
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Serialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        damage::Destroyed,
+        enemy::EnemyKind,
+        events::GameEvent,
+        player::{Player, PlayerStats},
+        terrain::waves::Waves,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PlayerProgress>();
+    app.init_resource::<PickupMesh>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_progress);
+    app.add_systems(
+        Update,
+        (drop_loot, drift_pickups, collect_pickups, expire_pickups)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const PICKUP_LIFETIME_SECS: f32 = 20.0;
+/// Pickups start to fade out this long before they sink.
+const PICKUP_FADE_SECS: f32 = 4.0;
+const PICKUP_SCATTER: f32 = 60.0;
+const PICKUP_RADIUS: f32 = 12.0;
+const DRIFT_SPEED: f32 = 30.0;
+const MAGNET_SPEED: f32 = 700.0;
+/// Pickups closer than this to the player are collected.
+const COLLECT_DISTANCE: f32 = 60.0;
+
+/// Gold and XP collected during the current run.
+#[derive(Resource, Debug)]
+pub struct PlayerProgress {
+    pub gold: u32,
+    /// XP towards the next level.
+    pub xp: u32,
+    pub level: u32,
+}
+
+impl Default for PlayerProgress {
+    fn default() -> Self {
+        Self {
+            gold: 0,
+            xp: 0,
+            level: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Reflect)]
+pub enum PickupKind {
+    Gold,
+    Xp,
+    /// Grants a random relic the player doesn't have yet.
+    Relic,
+}
+
+impl PickupKind {
+    pub fn color(self) -> Color {
+        match self {
+            PickupKind::Gold => Color::srgb(0.95, 0.78, 0.2),
+            PickupKind::Xp => Color::srgb(0.35, 0.9, 0.75),
+            PickupKind::Relic => Color::srgb(0.85, 0.3, 0.85),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LootEntry {
+    pub kind: PickupKind,
+    /// Chance of the entry dropping at all.
+    pub chance: f32,
+    /// Gold or XP per pickup.
+    pub amount: u32,
+    /// Inclusive range of the number of pickups dropped.
+    pub count: (u32, u32),
+}
+
+/// Everything a kind of enemy can drop.
+pub struct LootTable(pub &'static [LootEntry]);
+
+const RAIDER_LOOT: LootTable = LootTable(&[
+    LootEntry {
+        kind: PickupKind::Xp,
+        chance: 1.0,
+        amount: 5,
+        count: (1, 2),
+    },
+    LootEntry {
+        kind: PickupKind::Gold,
+        chance: 0.4,
+        amount: 3,
+        count: (1, 1),
+    },
+]);
+
+const FIRE_SHIP_LOOT: LootTable = LootTable(&[
+    LootEntry {
+        kind: PickupKind::Xp,
+        chance: 1.0,
+        amount: 4,
+        count: (1, 1),
+    },
+    LootEntry {
+        kind: PickupKind::Gold,
+        chance: 0.2,
+        amount: 2,
+        count: (1, 1),
+    },
+]);
+
+const BOSS_LOOT: LootTable = LootTable(&[
+    LootEntry {
+        kind: PickupKind::Xp,
+        chance: 1.0,
+        amount: 25,
+        count: (8, 12),
+    },
+    LootEntry {
+        kind: PickupKind::Gold,
+        chance: 1.0,
+        amount: 20,
+        count: (5, 8),
+    },
+    LootEntry {
+        kind: PickupKind::Relic,
+        chance: 1.0,
+        amount: 1,
+        count: (1, 1),
+    },
+]);
+
+impl EnemyKind {
+    pub fn loot_table(self) -> &'static LootTable {
+        match self {
+            EnemyKind::Raider => &RAIDER_LOOT,
+            EnemyKind::FireShip => &FIRE_SHIP_LOOT,
+            EnemyKind::Boss => &BOSS_LOOT,
+        }
+    }
+}
+
+impl LootTable {
+    /// Rolls the pickups of a single drop, as kind and amount per pickup.
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<(PickupKind, u32)> {
+        let mut drops = Vec::new();
+        for entry in self.0 {
+            if rng.random::<f32>() >= entry.chance {
+                continue;
+            }
+            let count = rng.random_range(entry.count.0..=entry.count.1);
+            drops.extend((0..count).map(|_| (entry.kind, entry.amount)));
+        }
+        drops
+    }
+}
+
+/// The mesh every pickup is drawn with.
+#[derive(Resource, Debug)]
+struct PickupMesh(Handle<Mesh>);
+
+impl FromWorld for PickupMesh {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        Self(meshes.add(Circle::new(PICKUP_RADIUS)))
+    }
+}
+
+#[derive(Component)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    pub amount: u32,
+    lifetime: Timer,
+}
+
+fn reset_progress(mut progress: ResMut<PlayerProgress>) {
+    *progress = PlayerProgress::default();
+}
+
+/// Only ships the player sank drop loot, fire ships blowing themselves up
+/// don't.
+fn drop_loot(
+    mut commands: Commands,
+    mut destroyed: MessageReader<Destroyed>,
+    mesh: Res<PickupMesh>,
+    players: Query<(), With<Player>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mut rng = rand::rng();
+    for event in destroyed.read() {
+        let Some(kind) = event.kind else {
+            continue;
+        };
+        if !event.source.is_some_and(|source| players.contains(source)) {
+            continue;
+        }
+        for (kind, amount) in kind.loot_table().roll(&mut rng) {
+            let offset = Vec2::new(
+                rng.random_range(-PICKUP_SCATTER..PICKUP_SCATTER),
+                rng.random_range(-PICKUP_SCATTER..PICKUP_SCATTER),
+            );
+            commands.spawn((
+                Name::new("Pickup"),
+                Pickup {
+                    kind,
+                    amount,
+                    lifetime: Timer::from_seconds(PICKUP_LIFETIME_SECS, TimerMode::Once),
+                },
+                Mesh2d(mesh.0.clone()),
+                MeshMaterial2d(materials.add(kind.color())),
+                Transform::from_translation((event.position + offset).extend(0.5)),
+                DespawnOnExit(Screen::Gameplay),
+            ));
+        }
+    }
+}
+
+/// Pickups bob along with the waves, like the ships do.
+fn drift_pickups(
+    time: Res<Time>,
+    waves: Single<&Waves>,
+    mut pickups: Query<&mut Transform, With<Pickup>>,
+) {
+    for mut transform in &mut pickups {
+        let (wave_dir, _wave_height, up) =
+            waves.wave_height(transform.translation.xy(), time.elapsed_secs());
+        if wave_dir.is_nan() {
+            continue;
+        }
+        let drift = if up { -wave_dir } else { wave_dir * 0.5 };
+        transform.translation += (drift * DRIFT_SPEED * time.delta_secs()).extend(0.0);
+    }
+}
+
+fn collect_pickups(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut progress: ResMut<PlayerProgress>,
+    mut events: MessageWriter<GameEvent>,
+    player: Single<(&Transform, &PlayerStats), With<Player>>,
+    mut pickups: Query<(Entity, &Pickup, &mut Transform), Without<Player>>,
+) {
+    let (player_transform, stats) = player.into_inner();
+    let player = player_transform.translation.xy();
+    for (entity, pickup, mut transform) in &mut pickups {
+        let to_player = player - transform.translation.xy();
+        let distance = to_player.length();
+        if distance < COLLECT_DISTANCE {
+            events.write(GameEvent::PickedUp { kind: pickup.kind });
+            match pickup.kind {
+                PickupKind::Gold => {
+                    progress.gold += pickup.amount;
+                    events.write(GameEvent::GoldEarned {
+                        amount: pickup.amount,
+                    });
+                }
+                PickupKind::Xp => progress.xp += pickup.amount,
+                PickupKind::Relic => {
+                    events.write(GameEvent::RelicFound);
+                }
+            }
+            commands.entity(entity).despawn();
+        } else if distance < stats.pickup_radius {
+            let step = (MAGNET_SPEED * time.delta_secs()).min(distance);
+            transform.translation += (to_player / distance * step).extend(0.0);
+        }
+    }
+}
+
+fn expire_pickups(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut pickups: Query<(Entity, &mut Pickup, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, mut pickup, material) in &mut pickups {
+        pickup.lifetime.tick(time.delta());
+        if pickup.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let remaining = pickup.lifetime.remaining_secs();
+        if remaining < PICKUP_FADE_SECS
+            && let Some(m) = materials.get_mut(material.0.id())
+        {
+            m.color.set_alpha(remaining / PICKUP_FADE_SECS);
+        }
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[allow(unused)]
+    use crate::demo::{enemy::EnemyKind, loot::PickupKind};
+
+    #[test]
+    fn loot_tables() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..100 {
+            let raider = EnemyKind::Raider.loot_table().roll(&mut rng);
+            assert!(raider.iter().any(|(kind, _)| *kind == PickupKind::Xp));
+            let boss = EnemyKind::Boss.loot_table().roll(&mut rng);
+            assert!(boss.len() >= 13);
+        }
+    }
+}
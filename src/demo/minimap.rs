@@ -0,0 +1,385 @@
+//! A map of the world in the corner of the screen.
+//!
+//! The map is drawn from the heights of all [`TerrainChunk`]s into a small
+//! [`Image`], one pixel per terrain cell, and redrawn whenever chunks are
+//! added or explored. Cells the player hasn't seen yet stay dark. Markers for
+//! the player, enemies, pickups and spawn zones are laid over it. Pressing M
+//! switches between the corner widget and a large map in the middle of the
+//! screen.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        enemy::Enemy,
+        forward_vec,
+        loot::Pickup,
+        player::Player,
+        spawning::SpawnRegion,
+        terrain::{
+            fog::ExploredCells,
+            height::{CHUNK_SIZE_PIXELS, SQUARE, TerrainChunk, WATER_LEVEL, world_2_chunk},
+        },
+    },
+    screens::Screen,
+    theme::palette::{BAR_BACKGROUND, ENEMY_MARKER, LABEL_TEXT},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Minimap>();
+    app.add_observer(track_enemy);
+    app.add_observer(track_pickup);
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_minimap);
+    app.add_systems(
+        Update,
+        (
+            toggle_map.run_if(input_just_pressed(KeyCode::KeyM)),
+            draw_terrain,
+            update_map_layout,
+            update_markers,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        update_player_marker
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Water this far below the water level shows up as shallows.
+const SHALLOWS_DEPTH: f32 = 0.3;
+const MINIMAP_HEIGHT: f32 = 200.0;
+/// Height of the expanded map, in percent of the window height.
+const EXPANDED_HEIGHT: f32 = 80.0;
+const MARKER_SIZE: f32 = 6.0;
+
+const LAND_COLOR: [u8; 4] = [121, 115, 45, 255];
+const SHALLOWS_COLOR: [u8; 4] = [70, 120, 190, 255];
+const WATER_COLOR: [u8; 4] = [25, 40, 110, 255];
+/// Outside of any chunk, or not explored yet.
+const VOID_COLOR: [u8; 4] = [10, 10, 20, 255];
+const SPAWN_ZONE_COLOR: Color = Color::srgba(0.878, 0.314, 0.235, 0.25);
+
+/// The state of the map.
+#[derive(Resource, Debug, Default)]
+struct Minimap {
+    /// The part of the world the map shows.
+    bounds: Rect,
+    expanded: bool,
+}
+
+#[derive(Component)]
+struct MinimapRoot;
+
+#[derive(Component)]
+struct PlayerMarker;
+
+/// A marker following an entity around the map.
+#[derive(Component)]
+struct MapMarker(Entity);
+
+/// A spawn zone drawn on the map.
+#[derive(Component)]
+struct ZoneMarker(Rect);
+
+fn terrain_color(height: Option<f32>) -> [u8; 4] {
+    match height {
+        None => VOID_COLOR,
+        Some(height) if height > WATER_LEVEL => LAND_COLOR,
+        Some(height) if height > WATER_LEVEL - SHALLOWS_DEPTH => SHALLOWS_COLOR,
+        Some(_) => WATER_COLOR,
+    }
+}
+
+/// Position of a world point on the map, from (0, 0) in the top left to
+/// (1, 1) in the bottom right corner.
+fn map_position(bounds: Rect, point: Vec2) -> Vec2 {
+    let relative = (point - bounds.min) / bounds.size();
+    Vec2::new(relative.x, 1.0 - relative.y)
+}
+
+fn spawn_minimap(mut commands: Commands, mut minimap: ResMut<Minimap>) {
+    *minimap = Minimap::default();
+    commands.spawn((
+        Name::new("Minimap"),
+        MinimapRoot,
+        Node {
+            position_type: PositionType::Absolute,
+            overflow: Overflow::clip(),
+            border: UiRect::all(px(2)),
+            ..default()
+        },
+        ImageNode::default(),
+        BorderColor::all(BAR_BACKGROUND),
+        BorderRadius::all(px(4)),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![(
+            Name::new("Player Marker"),
+            PlayerMarker,
+            marker_node(MARKER_SIZE * 2.0),
+            Text::new("^"),
+            TextFont::from_font_size(MARKER_SIZE * 3.0),
+            TextColor(LABEL_TEXT),
+            UiTransform::default(),
+            ZIndex(1),
+        )],
+    ));
+}
+
+fn marker_node(size: f32) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: px(size),
+        height: px(size),
+        margin: UiRect {
+            left: px(-size / 2.0),
+            top: px(-size / 2.0),
+            ..default()
+        },
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+fn dot(name: &'static str, target: Entity, color: Color) -> impl Bundle {
+    (
+        Name::new(name),
+        MapMarker(target),
+        marker_node(MARKER_SIZE),
+        BorderRadius::MAX,
+        BackgroundColor(color),
+        Visibility::Hidden,
+    )
+}
+
+fn track_enemy(
+    add: On<Add, Enemy>,
+    root: Query<Entity, With<MinimapRoot>>,
+    mut commands: Commands,
+) {
+    if let Ok(root) = root.single() {
+        commands
+            .entity(root)
+            .with_child(dot("Enemy Marker", add.entity, ENEMY_MARKER));
+    }
+}
+
+fn track_pickup(
+    add: On<Add, Pickup>,
+    pickups: Query<&Pickup>,
+    root: Query<Entity, With<MinimapRoot>>,
+    mut commands: Commands,
+) {
+    if let Ok(root) = root.single()
+        && let Ok(pickup) = pickups.get(add.entity)
+    {
+        commands
+            .entity(root)
+            .with_child(dot("Pickup Marker", add.entity, pickup.kind.color()));
+    }
+}
+
+fn toggle_map(mut minimap: ResMut<Minimap>) {
+    minimap.expanded = !minimap.expanded;
+}
+
+/// Redraws the map whenever chunks are added or explored.
+fn draw_terrain(
+    mut commands: Commands,
+    mut minimap: ResMut<Minimap>,
+    mut images: ResMut<Assets<Image>>,
+    chunks: Query<(&TerrainChunk, &ExploredCells, &Transform)>,
+    added: Query<(), Added<TerrainChunk>>,
+    explored: Query<(), Changed<ExploredCells>>,
+    regions: Query<&SpawnRegion>,
+    root: Single<(Entity, &mut ImageNode), With<MinimapRoot>>,
+    zones: Query<Entity, With<ZoneMarker>>,
+) {
+    let (root, mut image_node) = root.into_inner();
+    let first_draw = minimap.bounds.is_empty();
+    if explored.is_empty() && !first_draw {
+        return;
+    }
+    let half_chunk = CHUNK_SIZE_PIXELS as f32 / 2.0;
+    let Some(bounds) = chunks
+        .iter()
+        .map(|(.., transform)| {
+            Rect::from_center_half_size(transform.translation.xy(), Vec2::splat(half_chunk))
+        })
+        .reduce(|a, b| a.union(b))
+    else {
+        return;
+    };
+    if minimap.bounds != bounds {
+        minimap.bounds = bounds;
+    }
+
+    let cell = (CHUNK_SIZE_PIXELS / SQUARE) as f32;
+    let size = (bounds.size() / cell).round().as_uvec2();
+    let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+    // Images start at the top, the world grows upwards.
+    for row in (0..size.y).rev() {
+        for column in 0..size.x {
+            let point = bounds.min + (Vec2::new(column as f32, row as f32) + 0.5) * cell;
+            // Unexplored cells stay hidden.
+            let height = chunks.iter().find_map(|(chunk, cells, transform)| {
+                let local = point - transform.translation.xy();
+                let height = chunk.height_at(local)?;
+                let (x, y) = world_2_chunk(local);
+                Some(cells.is_explored(x, y).then_some(height))
+            });
+            data.extend(terrain_color(height.flatten()));
+        }
+    }
+
+    if let Some(image) = images.get_mut(&image_node.image)
+        && image.size() == size
+    {
+        image.data = Some(data);
+    } else {
+        let mut image = Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::all(),
+        );
+        image.sampler = ImageSampler::nearest();
+        image_node.image = images.add(image);
+    }
+
+    if added.is_empty() && !first_draw {
+        return;
+    }
+    for zone in &zones {
+        commands.entity(zone).despawn();
+    }
+    for region in &regions {
+        commands.entity(root).with_child((
+            Name::new("Spawn Zone"),
+            ZoneMarker(region.area),
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            BackgroundColor(SPAWN_ZONE_COLOR),
+        ));
+    }
+}
+
+fn update_map_layout(
+    minimap: Res<Minimap>,
+    mut root: Single<&mut Node, With<MinimapRoot>>,
+    mut zones: Query<(&ZoneMarker, &mut Node), Without<MinimapRoot>>,
+) {
+    if !minimap.is_changed() || minimap.bounds.is_empty() {
+        return;
+    }
+    let bounds = minimap.bounds;
+    root.aspect_ratio = Some(bounds.width() / bounds.height().max(1.0));
+    if minimap.expanded {
+        root.height = vh(EXPANDED_HEIGHT);
+        root.top = vh((100.0 - EXPANDED_HEIGHT) / 2.0);
+        root.right = Val::Auto;
+        root.left = percent(50);
+        root.margin.left = vh(-EXPANDED_HEIGHT / 2.0 * root.aspect_ratio.unwrap_or(1.0));
+    } else {
+        root.height = px(MINIMAP_HEIGHT);
+        root.top = px(16);
+        root.right = px(16);
+        root.left = Val::Auto;
+        root.margin.left = Val::ZERO;
+    }
+
+    for (ZoneMarker(area), mut node) in &mut zones {
+        let top_left = map_position(bounds, Vec2::new(area.min.x, area.max.y));
+        let size = area.size() / bounds.size();
+        node.left = percent(top_left.x * 100.0);
+        node.top = percent(top_left.y * 100.0);
+        node.width = percent(size.x * 100.0);
+        node.height = percent(size.y * 100.0);
+    }
+}
+
+fn update_markers(
+    mut commands: Commands,
+    minimap: Res<Minimap>,
+    targets: Query<&GlobalTransform>,
+    mut markers: Query<(Entity, &MapMarker, &mut Node, &mut Visibility)>,
+) {
+    if minimap.bounds.is_empty() {
+        return;
+    }
+    for (entity, MapMarker(target), mut node, mut visibility) in &mut markers {
+        let Ok(target) = targets.get(*target) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let position = map_position(minimap.bounds, target.translation().xy());
+        if position.cmplt(Vec2::ZERO).any() || position.cmpgt(Vec2::ONE).any() {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+        node.left = percent(position.x * 100.0);
+        node.top = percent(position.y * 100.0);
+    }
+}
+
+fn update_player_marker(
+    minimap: Res<Minimap>,
+    player: Single<&Transform, With<Player>>,
+    marker: Single<(&mut Node, &mut UiTransform), With<PlayerMarker>>,
+) {
+    if minimap.bounds.is_empty() {
+        return;
+    }
+    let (mut node, mut transform) = marker.into_inner();
+    let position = map_position(minimap.bounds, player.translation.xy());
+    node.left = percent(position.x * 100.0);
+    node.top = percent(position.y * 100.0);
+    // UI rotations are clockwise, world rotations counterclockwise.
+    let forward = forward_vec(**player);
+    transform.rotation = Rot2::radians(forward.x.atan2(forward.y));
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::math::{Rect, Vec2};
+
+    #[allow(unused)]
+    use crate::demo::minimap::{
+        LAND_COLOR, SHALLOWS_COLOR, VOID_COLOR, WATER_COLOR, map_position, terrain_color,
+    };
+
+    #[test]
+    fn map_shows_terrain_and_positions() {
+        assert_eq!(terrain_color(None), VOID_COLOR);
+        assert_eq!(terrain_color(Some(0.9)), LAND_COLOR);
+        assert_eq!(terrain_color(Some(0.4)), SHALLOWS_COLOR);
+        assert_eq!(terrain_color(Some(-0.5)), WATER_COLOR);
+
+        let bounds = Rect::new(-100.0, -50.0, 100.0, 50.0);
+        assert_eq!(map_position(bounds, Vec2::new(-100.0, 50.0)), Vec2::ZERO);
+        assert_eq!(map_position(bounds, Vec2::new(100.0, -50.0)), Vec2::ONE);
+        assert_eq!(map_position(bounds, Vec2::ZERO), Vec2::splat(0.5));
+    }
+}
@@ -6,14 +6,39 @@
 use avian2d::prelude::PhysicsLayer;
 use bevy::prelude::*;
 
+mod abilities;
+mod affixes;
+mod boss;
+pub mod camera;
+mod damage;
 pub mod enemy;
+mod events;
+mod fire_ship;
+pub mod high_scores;
+mod hit_feedback;
+mod hud;
+mod hull;
 pub mod level;
+mod loot;
+mod minimap;
 mod movement;
+mod navigation;
 pub mod player;
+mod ramming;
+pub mod relics;
+pub mod run_stats;
+pub mod ships;
+mod spawning;
+pub mod stats;
+mod status_effects;
+mod steering;
+mod surfing;
 mod terrain;
+pub mod upgrades;
+pub mod wave_director;
 pub mod weapons;
 
-#[derive(PhysicsLayer, Default)]
+#[derive(PhysicsLayer, Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameCollisionLayer {
     #[default]
     Terrain,
@@ -22,17 +47,57 @@ pub enum GameCollisionLayer {
 }
 
 #[derive(Component)]
-pub struct Health(i32);
+pub struct Health {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl Health {
+    pub fn new(max: i32) -> Health {
+        Health { current: max, max }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        (self.current as f32 / self.max as f32).clamp(0.0, 1.0)
+    }
+}
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         level::plugin,
+        minimap::plugin,
         movement::plugin,
+        navigation::plugin,
         player::plugin,
+        abilities::plugin,
+        camera::plugin,
+        hit_feedback::plugin,
+        ramming::plugin,
+        run_stats::plugin,
+        surfing::plugin,
+        steering::plugin,
         terrain::TerrainPlugin,
         enemy::EnemyPlugin,
         weapons::WeaponPlugin,
     ));
+    app.add_plugins((
+        affixes::plugin,
+        boss::plugin,
+        damage::plugin,
+        events::plugin,
+        fire_ship::plugin,
+        hud::plugin,
+        hull::plugin,
+        loot::plugin,
+        relics::plugin,
+        ships::plugin,
+        spawning::plugin,
+        stats::plugin,
+        status_effects::plugin,
+        upgrades::plugin,
+        wave_director::plugin,
+    ));
+    app.add_plugins(high_scores::plugin);
 }
 
 pub fn forward_vec(transform: Transform) -> Vec2 {
@@ -45,6 +45,15 @@ pub struct MovementController {
     /// Maximum speed in world units per second.
     /// 1 world unit = 1 pixel when using the default 2D camera and no physics engine.
     pub max_speed: f32,
+
+    /// Scales intent, e.g. set by status effects slowing the ship down.
+    pub speed_multiplier: f32,
+
+    /// Scales forward intent by how well the ship rides the waves.
+    pub wave_multiplier: f32,
+
+    /// Scales forward intent and top speed, e.g. set by the Swift affix.
+    pub affix_multiplier: f32,
 }
 
 impl Default for MovementController {
@@ -53,22 +62,46 @@ impl Default for MovementController {
             intent: 0.0,
             rotation_intent: 0.0,
             max_speed: 400.0,
+            speed_multiplier: 1.0,
+            wave_multiplier: 1.0,
+            affix_multiplier: 1.0,
         }
     }
 }
 
+impl MovementController {
+    /// Speed past which thrust stops pushing the ship. Slowing effects lower
+    /// it, surfing and affixes raise it.
+    pub fn top_speed(&self) -> f32 {
+        self.max_speed
+            * self.speed_multiplier
+            * self.affix_multiplier
+            * self.wave_multiplier.max(1.0)
+    }
+}
+
 fn apply_movement(
     _time: Res<Time>,
     mut movement_query: Query<(&MovementController, &Transform, Forces)>,
 ) {
     for (controller, transform, mut forces) in &mut movement_query {
-        forces.apply_angular_impulse(controller.rotation_intent * 600.0);
+        forces.apply_angular_impulse(
+            controller.rotation_intent * controller.speed_multiplier * 600.0,
+        );
         let angle = transform.rotation.to_euler(EulerRot::XYZ).2 + f32::consts::FRAC_PI_2;
         let forward = Vec2::new(angle.cos(), angle.sin());
 
-        let new_force = forward * controller.intent * 300.0; //* time.delta_secs();
+        let new_force = forward
+            * controller.intent
+            * controller.speed_multiplier
+            * controller.wave_multiplier
+            * controller.affix_multiplier
+            * 300.0; //* time.delta_secs();
 
-        forces.apply_force(new_force);
+        // Bursts and knockback still carry the ship past its top speed.
+        if forces.linear_velocity().dot(new_force.normalize_or_zero()) < controller.top_speed() {
+            forces.apply_force(new_force);
+        }
     }
 }
 
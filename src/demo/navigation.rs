@@ -0,0 +1,410 @@
+//! Finding your way around.
+//!
+//! Enemies, bosses, objectives and ports outside of the view get an arrow at
+//! the edge of the screen pointing at them, which shrinks and fades the
+//! further away they are. A compass strip at the top shows the heading of the
+//! player's ship, the same points of interest except for regular enemies, and
+//! the direction the [`Wind`] blows towards.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        boss::Boss,
+        enemy::Enemy,
+        forward_vec,
+        loot::{Pickup, PickupKind},
+        player::Player,
+        terrain::{ports::Port, wind::Wind},
+    },
+    screens::Screen,
+    theme::{
+        palette::{
+            BAR_BACKGROUND, BOSS_MARKER, ENEMY_MARKER, LABEL_TEXT, OBJECTIVE_MARKER, PORT_MARKER,
+            WIND_MARKER,
+        },
+        prelude::*,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(track_enemy);
+    app.add_observer(track_objective);
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_compass);
+    app.add_systems(
+        Update,
+        (track_ports, update_indicators, update_compass)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Distance of the arrows from the edge of the screen.
+const EDGE_MARGIN: f32 = 28.0;
+/// Arrows are at their largest up to this distance from the player...
+const NEAR_DISTANCE: f32 = 800.0;
+/// ...and at their smallest from this distance on.
+const FAR_DISTANCE: f32 = 4000.0;
+const MIN_OPACITY: f32 = 0.3;
+
+const COMPASS_WIDTH: f32 = 480.0;
+/// The angle the compass strip covers, centered on the heading.
+const COMPASS_SPAN: f32 = PI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarkerKind {
+    Enemy,
+    Boss,
+    Objective,
+    Port,
+}
+
+impl MarkerKind {
+    fn color(self) -> Color {
+        match self {
+            MarkerKind::Enemy => ENEMY_MARKER,
+            MarkerKind::Boss => BOSS_MARKER,
+            MarkerKind::Objective => OBJECTIVE_MARKER,
+            MarkerKind::Port => PORT_MARKER,
+        }
+    }
+
+    /// Font size of the arrow at its largest and smallest.
+    fn size(self) -> (f32, f32) {
+        match self {
+            MarkerKind::Enemy => (32.0, 16.0),
+            MarkerKind::Boss => (56.0, 32.0),
+            MarkerKind::Objective => (44.0, 24.0),
+            MarkerKind::Port => (36.0, 20.0),
+        }
+    }
+}
+
+/// An arrow at the edge of the screen pointing at an off-screen entity.
+#[derive(Component, Debug)]
+struct Indicator {
+    target: Entity,
+    kind: MarkerKind,
+}
+
+/// Where a mark on the compass strip points.
+#[derive(Component, Debug, Clone, Copy)]
+enum CompassMark {
+    /// A fixed bearing, in radians clockwise from north.
+    Bearing(f32),
+    Target(Entity),
+    Wind,
+}
+
+#[derive(Component)]
+struct CompassStrip;
+
+/// Clockwise angle from north (world up) to `dir`.
+fn bearing(dir: Vec2) -> f32 {
+    dir.x.atan2(dir.y)
+}
+
+/// Horizontal position of a bearing on the compass strip, from -1.0 on the
+/// left edge to 1.0 on the right edge. `None` if it is outside of the strip.
+fn compass_offset(bearing: f32, heading: f32) -> Option<f32> {
+    let relative = (bearing - heading + PI).rem_euclid(2.0 * PI) - PI;
+    let offset = relative / (COMPASS_SPAN / 2.0);
+    (offset.abs() <= 1.0).then_some(offset)
+}
+
+/// Clamps `point` to the rectangle `half_size - margin` around `center`, along
+/// the line from the center. `None` if the point is inside the rectangle.
+fn edge_position(center: Vec2, half_size: Vec2, point: Vec2, margin: f32) -> Option<Vec2> {
+    let inner = (half_size - margin).max(Vec2::ONE);
+    let offset = point - center;
+    let scale = (offset.abs() / inner).max_element();
+    (scale > 1.0).then(|| center + offset / scale)
+}
+
+fn spawn_indicator(commands: &mut Commands, target: Entity, kind: MarkerKind) {
+    commands.spawn((
+        Name::new("Indicator"),
+        Indicator { target, kind },
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Text::new(">"),
+        TextFont::from_font_size(kind.size().0),
+        TextColor(kind.color()),
+        UiTransform::default(),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn compass_mark(mark: CompassMark, text: &str, color: Color) -> impl Bundle {
+    (
+        Name::new("Compass Mark"),
+        mark,
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Text::new(text),
+        TextFont::from_font_size(20.0),
+        TextColor(color),
+        Visibility::Hidden,
+    )
+}
+
+fn track_enemy(
+    add: On<Add, Enemy>,
+    bosses: Query<(), With<Boss>>,
+    strip: Query<Entity, With<CompassStrip>>,
+    mut commands: Commands,
+) {
+    let is_boss = bosses.contains(add.entity);
+    let kind = if is_boss {
+        MarkerKind::Boss
+    } else {
+        MarkerKind::Enemy
+    };
+    spawn_indicator(&mut commands, add.entity, kind);
+    if is_boss && let Ok(strip) = strip.single() {
+        commands.entity(strip).with_child(compass_mark(
+            CompassMark::Target(add.entity),
+            "B",
+            kind.color(),
+        ));
+    }
+}
+
+fn track_objective(
+    add: On<Add, Pickup>,
+    pickups: Query<&Pickup>,
+    strip: Query<Entity, With<CompassStrip>>,
+    mut commands: Commands,
+) {
+    if !pickups
+        .get(add.entity)
+        .is_ok_and(|pickup| pickup.kind == PickupKind::Relic)
+    {
+        return;
+    }
+    spawn_indicator(&mut commands, add.entity, MarkerKind::Objective);
+    if let Ok(strip) = strip.single() {
+        commands.entity(strip).with_child(compass_mark(
+            CompassMark::Target(add.entity),
+            "R",
+            MarkerKind::Objective.color(),
+        ));
+    }
+}
+
+/// Ports are spawned with the terrain, on the same frame as the compass, so
+/// they are picked up once both are there.
+fn track_ports(
+    ports: Query<Entity, Added<Port>>,
+    strip: Single<Entity, With<CompassStrip>>,
+    mut commands: Commands,
+) {
+    for port in &ports {
+        spawn_indicator(&mut commands, port, MarkerKind::Port);
+        commands.entity(*strip).with_child(compass_mark(
+            CompassMark::Target(port),
+            "P",
+            MarkerKind::Port.color(),
+        ));
+    }
+}
+
+fn update_indicators(
+    mut commands: Commands,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    player: Single<&Transform, With<Player>>,
+    targets: Query<&GlobalTransform, Without<Camera>>,
+    mut indicators: Query<(
+        Entity,
+        &Indicator,
+        &mut Node,
+        &mut UiTransform,
+        &mut TextFont,
+        &mut TextColor,
+        &mut Visibility,
+    )>,
+) {
+    let (camera, camera_transform) = camera.into_inner();
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+    let half_size = viewport / 2.0;
+
+    for (entity, indicator, mut node, mut transform, mut font, mut color, mut visibility) in
+        &mut indicators
+    {
+        let Ok(target) = targets.get(indicator.target) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let Some(position) = camera
+            .world_to_viewport(camera_transform, target.translation())
+            .ok()
+            .and_then(|point| edge_position(half_size, half_size, point, EDGE_MARGIN))
+        else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let distance = player.translation.xy().distance(target.translation().xy());
+        let closeness =
+            1.0 - ((distance - NEAR_DISTANCE) / (FAR_DISTANCE - NEAR_DISTANCE)).clamp(0.0, 1.0);
+        let (largest, smallest) = indicator.kind.size();
+        font.font_size = smallest + (largest - smallest) * closeness;
+        color.0 = indicator
+            .kind
+            .color()
+            .with_alpha(MIN_OPACITY + (1.0 - MIN_OPACITY) * closeness);
+
+        // Screen space has y pointing down, and UI rotations are clockwise.
+        let dir = position - half_size;
+        transform.rotation = Rot2::radians(dir.y.atan2(dir.x));
+        node.left = px(position.x - font.font_size / 2.0);
+        node.top = px(position.y - font.font_size / 2.0);
+    }
+}
+
+fn spawn_compass(mut commands: Commands) {
+    let cardinals = [
+        (0.0, "N"),
+        (PI / 4.0, "NE"),
+        (PI / 2.0, "E"),
+        (3.0 * PI / 4.0, "SE"),
+        (PI, "S"),
+        (-3.0 * PI / 4.0, "SW"),
+        (-PI / 2.0, "W"),
+        (-PI / 4.0, "NW"),
+    ];
+    commands
+        .spawn((
+            Name::new("Compass"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(12),
+                width: percent(100),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Name::new("Compass Strip"),
+                    CompassStrip,
+                    Node {
+                        width: px(COMPASS_WIDTH),
+                        height: px(28),
+                        ..default()
+                    },
+                    BorderRadius::all(px(4)),
+                    BackgroundColor(BAR_BACKGROUND),
+                ))
+                .with_children(|strip| {
+                    for (bearing, text) in cardinals {
+                        strip.spawn(compass_mark(
+                            CompassMark::Bearing(bearing),
+                            text,
+                            LABEL_TEXT,
+                        ));
+                    }
+                    strip.spawn(compass_mark(CompassMark::Wind, "~", WIND_MARKER));
+                    strip.spawn((
+                        widget::icon("Heading", px(2), LABEL_TEXT),
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: px(COMPASS_WIDTH / 2.0 - 1.0),
+                            width: px(2),
+                            height: percent(100),
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
+
+fn update_compass(
+    mut commands: Commands,
+    wind: Res<Wind>,
+    player: Single<&Transform, With<Player>>,
+    targets: Query<&GlobalTransform, Without<Player>>,
+    mut marks: Query<(
+        Entity,
+        &CompassMark,
+        &ComputedNode,
+        &mut Node,
+        &mut Visibility,
+    )>,
+) {
+    let position = player.translation.xy();
+    let heading = bearing(forward_vec(**player));
+
+    for (entity, mark, computed, mut node, mut visibility) in &mut marks {
+        let mark_bearing = match *mark {
+            CompassMark::Bearing(bearing) => Some(bearing),
+            CompassMark::Target(target) => match targets.get(target) {
+                Ok(target) => Some(bearing(target.translation().xy() - position)),
+                Err(_) => {
+                    commands.entity(entity).despawn();
+                    continue;
+                }
+            },
+            CompassMark::Wind => Some(bearing(wind.direction)),
+        };
+        let Some(offset) = mark_bearing.and_then(|bearing| compass_offset(bearing, heading)) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        let width = computed.size().x * computed.inverse_scale_factor();
+        node.left = px((offset + 1.0) * COMPASS_WIDTH / 2.0 - width / 2.0);
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use std::f32::consts::PI;
+
+    #[allow(unused)]
+    use bevy::math::Vec2;
+
+    #[allow(unused)]
+    use crate::demo::navigation::{bearing, compass_offset, edge_position};
+
+    #[test]
+    fn markers_are_placed_on_edges_and_compass() {
+        let center = Vec2::new(400.0, 300.0);
+        assert_eq!(
+            edge_position(center, center, Vec2::new(500.0, 300.0), 0.0),
+            None
+        );
+        assert_eq!(
+            edge_position(center, center, Vec2::new(1600.0, 300.0), 0.0),
+            Some(Vec2::new(800.0, 300.0))
+        );
+        assert_eq!(
+            edge_position(center, center, Vec2::new(400.0, -300.0), 100.0),
+            Some(Vec2::new(400.0, 100.0))
+        );
+
+        assert_eq!(bearing(Vec2::Y), 0.0);
+        assert_eq!(bearing(Vec2::X), PI / 2.0);
+        // Heading east, north is on the left, south on the right and west out of sight.
+        assert!(compass_offset(PI / 4.0, PI / 2.0).is_some_and(|x| (x + 0.5).abs() < 1e-6));
+        assert!(compass_offset(3.0 * PI / 4.0, PI / 2.0).is_some_and(|x| (x - 0.5).abs() < 1e-6));
+        assert_eq!(compass_offset(-PI / 2.0, PI / 2.0), None);
+        assert_eq!(compass_offset(PI / 2.0, PI / 2.0), Some(0.0));
+    }
+}
@@ -1,6 +1,8 @@
 //! Player-specific behavior.
 
-use avian2d::prelude::{AngularDamping, Collider, CollisionLayers, LinearDamping, Mass, RigidBody};
+use avian2d::prelude::{
+    AngularDamping, CollisionEventsEnabled, CollisionLayers, LinearDamping, Mass, RigidBody,
+};
 use bevy::{
     prelude::*,
     render::render_resource::AsBindGroup,
@@ -10,19 +12,30 @@ use bevy::{
 use crate::{
     AppSystems, PausableSystems,
     demo::{
-        GameCollisionLayer,
+        GameCollisionLayer, Health,
+        abilities::Abilities,
+        hull::HullSections,
         movement::MovementController,
-        weapons::{WeaponSlots, WeaponType},
+        relics::Relics,
+        ships::ShipHull,
+        stats::{ModifierOp, ModifierSource, Stat, StatModifier, StatModifiers},
+        surfing::Surf,
+        weapons::WeaponSlots,
     },
     screens::Screen,
 };
 
-#[derive(Component)]
+/// The effective stats of the player, calculated from its
+/// [`StatModifiers`](crate::demo::stats::StatModifiers).
+#[derive(Component, Debug, Clone, PartialEq)]
 pub struct PlayerStats {
     pub projectile_damage_percentage: f32,
     pub projectile_speed_percentage: f32,
     pub projectile_rate_percentage: f32,
-    pub _explosion_damage_percentage: f32,
+    pub explosion_damage_percentage: f32,
+    pub ram_damage_percentage: f32,
+    /// Pickups within this distance are pulled towards the player.
+    pub pickup_radius: f32,
 }
 impl Default for PlayerStats {
     fn default() -> Self {
@@ -30,42 +43,57 @@ impl Default for PlayerStats {
             projectile_damage_percentage: 1.0,
             projectile_speed_percentage: 1.0,
             projectile_rate_percentage: 1.0,
-            _explosion_damage_percentage: 1.0,
+            explosion_damage_percentage: 1.0,
+            ram_damage_percentage: 1.0,
+            pickup_radius: 250.0,
         }
     }
 }
 
+/// The player's crew reloads five times as fast as the base reload times.
+const PLAYER_RELOAD: f32 = 0.2;
+
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (
-            record_player_directional_input
-                .in_set(AppSystems::RecordInput)
-                .in_set(PausableSystems),
-            follow_cam
-                .in_set(AppSystems::Update)
-                .in_set(PausableSystems),
-        ),
+        record_player_directional_input
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
     )
     .add_systems(Update, update_time.run_if(in_state(Screen::Gameplay)))
     .add_plugins(Material2dPlugin::<BoatMaterial>::default());
 }
 
-/// The player character.
+/// The player character, sailing a ship with the given hull.
 pub fn player(
-    max_speed: f32,
+    hull: &ShipHull,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<BoatMaterial>,
 ) -> impl Bundle {
-    let mesh = meshes.add(Rectangle::new(300.0, 500.0));
-    let material = materials.add(BoatMaterial { time: Vec4::ZERO });
+    let mesh = meshes.add(Rectangle::new(hull.size.x, hull.size.y));
+    let material = materials.add(BoatMaterial {
+        time: Vec4::ZERO,
+        hull: hull.shader_params(),
+    });
 
     let collision = CollisionLayers::new(
         GameCollisionLayer::Player,
         [GameCollisionLayer::Terrain, GameCollisionLayer::Enemy],
     );
-    let mut stats = PlayerStats::default();
-    stats.projectile_rate_percentage = 0.2;
+    let mut modifiers = StatModifiers::new(PlayerStats::default());
+    modifiers.add(StatModifier {
+        stat: Stat::ProjectileRate,
+        op: ModifierOp::Multiply(PLAYER_RELOAD),
+        source: ModifierSource::Ship,
+    });
+    let stats = modifiers.effective();
+    let mut slots = WeaponSlots::new(hull.broadside_mounts, hull.bow_mount);
+    for weapon in hull.starting_weapons {
+        slots.mount(*weapon, &stats);
+    }
+    if let Some(weapon) = hull.bow_weapon {
+        slots.mount_bow(weapon, &stats);
+    }
     (
         Name::new("Player"),
         Player,
@@ -73,55 +101,30 @@ pub fn player(
         MeshMaterial2d(material),
         Transform::from_translation(Vec3::ZERO),
         MovementController {
-            max_speed,
+            max_speed: hull.max_speed,
             ..default()
         },
-        RigidBody::Dynamic,
-        Mass(10.0),
-        AngularDamping(2.0),
-        LinearDamping(0.2),
-        Collider::rectangle(100.0, 200.0),
-        collision,
-        WeaponSlots {
-            left: [
-                Some(WeaponType::default_cannon(&stats)),
-                Some(WeaponType::default_cannon(&stats)),
-                Some(WeaponType::default_cannon(&stats)),
-            ],
-            right: [
-                Some(WeaponType::default_cannon(&stats)),
-                Some(WeaponType::default_cannon(&stats)),
-                Some(WeaponType::default_cannon(&stats)),
-            ],
-            front: Some(WeaponType::default_cannon(&stats)),
-            // front: None,
-        },
+        (
+            RigidBody::Dynamic,
+            Mass(hull.mass),
+            AngularDamping(hull.angular_damping),
+            LinearDamping(hull.linear_damping),
+            hull.collider(),
+            collision,
+            // Needed for ramming.
+            CollisionEventsEnabled,
+        ),
+        Health::new(hull.health),
+        HullSections::new(hull.health),
+        slots,
+        Abilities::default(),
+        Surf::default(),
+        Relics::default(),
+        modifiers,
         stats,
     )
 }
 
-fn follow_cam(
-    camera: Single<&mut Transform, (With<Camera>, Without<Player>)>,
-    player: Single<&Transform, (With<Player>, Without<Camera>)>,
-) {
-    let mut camera = camera.into_inner();
-
-    let player = player.into_inner();
-
-    camera.translation.x = player.translation.x;
-    camera.translation.y = player.translation.y;
-
-    // let cam_angle = camera.rotation.to_euler(EulerRot::XYZ).2;
-    // let player_angle = player.rotation.to_euler(EulerRot::XYZ).2;
-    // let diff = (cam_angle - player_angle).abs();
-
-    // if diff < 0.01 {
-    // camera.rotation = player.rotation;
-    // } else {
-    // camera.rotation = Quat::from_rotation_z(cam_angle - (cam_angle - player_angle) * 0.2);
-    // }
-}
-
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Player;
@@ -168,6 +171,9 @@ fn update_time(
 pub struct BoatMaterial {
     #[uniform(0)]
     time: Vec4,
+    /// See [`ShipHull::shader_params`].
+    #[uniform(1)]
+    hull: Vec4,
 }
 
 const BOAT_SHADER_PATH: &str = "shaders/boat.wesl";
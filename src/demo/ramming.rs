@@ -0,0 +1,202 @@
+//! Ramming enemies with the player's ship.
+//!
+//! When the player collides with an enemy, both ships take damage based on how
+//! hard the physics solver had to push them apart and on which part of each
+//! hull hits the other. Driving the bow into a broadside hurts the most, while
+//! hitting bow-first spares the own hull.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        damage::{DamageEvent, DamageKind, apply_damage},
+        enemy::{Emerging, Enemy},
+        forward_vec,
+        player::{Player, PlayerStats},
+        surfing::Surf,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        ram_enemies
+            .before(apply_damage)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Slower impacts are just bumps.
+const MIN_IMPACT_SPEED: f32 = 80.0;
+const DAMAGE_PER_SPEED: f32 = 0.15;
+/// Cosine between heading and contact direction above which the bow or stern is hit.
+const END_ALIGNMENT: f32 = 0.7;
+
+/// The part of a hull that is involved in a collision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HullPart {
+    Bow,
+    Stern,
+    Broadside,
+}
+
+impl HullPart {
+    /// The part of a ship heading along `forward` that faces `towards`.
+    pub fn facing(forward: Vec2, towards: Vec2) -> HullPart {
+        let alignment = forward.dot(towards.normalize_or_zero());
+        if alignment > END_ALIGNMENT {
+            HullPart::Bow
+        } else if alignment < -END_ALIGNMENT {
+            HullPart::Stern
+        } else {
+            HullPart::Broadside
+        }
+    }
+
+    /// How hard this part hits when it strikes another ship.
+    fn striking_factor(self) -> f32 {
+        match self {
+            HullPart::Bow => 1.0,
+            HullPart::Broadside => 0.4,
+            HullPart::Stern => 0.2,
+        }
+    }
+
+    /// How badly this part is hurt when it is struck.
+    fn struck_factor(self) -> f32 {
+        match self {
+            HullPart::Bow => 0.5,
+            HullPart::Broadside => 1.5,
+            HullPart::Stern => 1.2,
+        }
+    }
+}
+
+/// Damage dealt by a ship striking with `striking` into the `struck` part of another.
+pub fn ram_damage(impact_speed: f32, striking: HullPart, struck: HullPart) -> f32 {
+    if impact_speed < MIN_IMPACT_SPEED {
+        return 0.0;
+    }
+    impact_speed * DAMAGE_PER_SPEED * striking.striking_factor() * struck.struck_factor()
+}
+
+/// The change in closing speed caused by a contact `impulse` between two
+/// bodies, so that heavier ships don't hit harder just for being heavy.
+fn impact_speed(impulse: f32, mass1: f32, mass2: f32) -> f32 {
+    if mass1 <= 0.0 || mass2 <= 0.0 {
+        return 0.0;
+    }
+    impulse / (mass1 * mass2 / (mass1 + mass2))
+}
+
+fn ram_enemies(
+    mut collisions: MessageReader<CollisionStart>,
+    contacts: Collisions,
+    player: Single<(Entity, &Transform, &Mass, &PlayerStats, &Surf), With<Player>>,
+    enemies: Query<(&Transform, &Mass), (With<Enemy>, Without<Emerging>)>,
+    mut damage: MessageWriter<DamageEvent>,
+) {
+    let (player, player_transform, player_mass, stats, surf) = player.into_inner();
+    for event in collisions.read() {
+        let enemy = match (event.body1, event.body2) {
+            (Some(a), Some(b)) if a == player => b,
+            (Some(a), Some(b)) if b == player => a,
+            _ => continue,
+        };
+        let Ok((enemy_transform, enemy_mass)) = enemies.get(enemy) else {
+            continue;
+        };
+        let Some(pair) = contacts.get(event.collider1, event.collider2) else {
+            continue;
+        };
+
+        let to_enemy = (enemy_transform.translation - player_transform.translation).xy();
+        // The contact normal is more precise than the direction between the
+        // centers, but its orientation depends on the order of the colliders.
+        let normal = pair
+            .manifolds
+            .first()
+            .map(|manifold| manifold.normal)
+            .unwrap_or(to_enemy)
+            .normalize_or_zero();
+        let normal = if normal.dot(to_enemy) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        // Velocities are already resolved by the time this runs, but the
+        // impulses the solver applied to separate the ships are kept.
+        let impact_speed = impact_speed(
+            pair.total_normal_impulse_magnitude(),
+            player_mass.0,
+            enemy_mass.0,
+        );
+
+        let player_part = HullPart::facing(forward_vec(*player_transform), normal);
+        let enemy_part = HullPart::facing(forward_vec(*enemy_transform), -normal);
+
+        let dealt = ram_damage(impact_speed, player_part, enemy_part)
+            * stats.ram_damage_percentage
+            * (1.0 + surf.meter);
+        if dealt > 0.0 {
+            damage.write(DamageEvent {
+                target: enemy,
+                amount: dealt,
+                kind: DamageKind::Ram,
+                source: Some(player),
+                position: None,
+                weapon: None,
+            });
+        }
+        let taken = ram_damage(impact_speed, enemy_part, player_part);
+        if taken > 0.0 {
+            damage.write(DamageEvent {
+                target: player,
+                amount: taken,
+                kind: DamageKind::Ram,
+                source: Some(enemy),
+                position: None,
+                weapon: None,
+            });
+        }
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::math::Vec2;
+
+    #[allow(unused)]
+    use crate::demo::ramming::{HullPart, impact_speed, ram_damage};
+
+    #[test]
+    fn bow_into_broadside_is_strongest() {
+        assert_eq!(HullPart::facing(Vec2::Y, Vec2::Y), HullPart::Bow);
+        assert_eq!(HullPart::facing(Vec2::Y, Vec2::X), HullPart::Broadside);
+        assert_eq!(HullPart::facing(Vec2::Y, Vec2::NEG_Y), HullPart::Stern);
+
+        let parts = [HullPart::Bow, HullPart::Stern, HullPart::Broadside];
+        let strongest = ram_damage(400.0, HullPart::Bow, HullPart::Broadside);
+        for striking in parts {
+            for struck in parts {
+                assert!(ram_damage(400.0, striking, struck) <= strongest);
+            }
+        }
+        // Hitting bow-first takes less than getting hit in the side.
+        assert!(
+            ram_damage(400.0, HullPart::Broadside, HullPart::Bow)
+                < ram_damage(400.0, HullPart::Bow, HullPart::Broadside)
+        );
+        assert_eq!(ram_damage(50.0, HullPart::Bow, HullPart::Broadside), 0.0);
+
+        // Stopping two ships of 10 closing in at 200 takes an impulse of 1000.
+        assert_eq!(impact_speed(1000.0, 10.0, 10.0), 200.0);
+        assert_eq!(impact_speed(1000.0, 0.0, 10.0), 0.0);
+        assert_eq!(impact_speed(1000.0, 0.0, 0.0), 0.0);
+    }
+}
@@ -0,0 +1,186 @@
+//! Relics are passive items that react to [`GameEvent`]s.
+//!
+//! Bosses drop relic pickups, each one grants a random relic the player
+//! doesn't own yet. A relic either changes stats through the
+//! [`StatModifiers`], tagged with [`ModifierSource::Relic`], or has its own
+//! system reacting to the events it cares about.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::prelude::*;
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        GameCollisionLayer, Health,
+        events::GameEvent,
+        player::{Player, PlayerStats},
+        stats::{ModifierOp, ModifierSource, Stat, StatModifier, StatModifiers},
+        weapons::{CanonBall, Ricochet, WeaponMaterial, cannon_ball},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (grant_relics, powder_volley, last_stand, skipping_stones)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const VOLLEY_CHANCE: f32 = 0.1;
+const VOLLEY_BALLS: usize = 8;
+const VOLLEY_DAMAGE: f32 = 30.0;
+const VOLLEY_SPEED: f32 = 500.0;
+
+const LAST_STAND_HEALTH: f32 = 0.3;
+/// Multiplier of reload times, so 50% faster reloads.
+const LAST_STAND_RATE: f32 = 1.0 / 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum Relic {
+    PowderMonkey,
+    LastStand,
+    SkippingStones,
+}
+
+impl Relic {
+    pub const ALL: [Relic; 3] = [Relic::PowderMonkey, Relic::LastStand, Relic::SkippingStones];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Relic::PowderMonkey => "Powder Monkey",
+            Relic::LastStand => "Last Stand",
+            Relic::SkippingStones => "Skipping Stones",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Relic::PowderMonkey => {
+                "Sunk ships have a 10% chance to burst into a volley of cannon balls"
+            }
+            Relic::LastStand => "Below 30% hull, reload 50% faster",
+            Relic::SkippingStones => "Cannon balls bounce off islands once",
+        }
+    }
+}
+
+/// The relics owned by the player, in the order they were found.
+#[derive(Component, Debug, Default)]
+pub struct Relics(pub Vec<Relic>);
+
+impl Relics {
+    pub fn has(&self, relic: Relic) -> bool {
+        self.0.contains(&relic)
+    }
+}
+
+fn grant_relics(mut events: MessageReader<GameEvent>, mut relics: Single<&mut Relics>) {
+    let mut rng = rand::rng();
+    for event in events.read() {
+        if !matches!(event, GameEvent::RelicFound) {
+            continue;
+        }
+        let missing: Vec<Relic> = Relic::ALL
+            .into_iter()
+            .filter(|relic| !relics.has(*relic))
+            .collect();
+        if let Some(relic) = missing.choose(&mut rng) {
+            relics.0.push(*relic);
+        }
+    }
+}
+
+fn powder_volley(
+    mut commands: Commands,
+    mut events: MessageReader<GameEvent>,
+    player: Single<(Entity, &PlayerStats, &Relics), With<Player>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<WeaponMaterial>>,
+) {
+    let (entity, stats, relics) = player.into_inner();
+    if !relics.has(Relic::PowderMonkey) {
+        return;
+    }
+    let mut rng = rand::rng();
+    for event in events.read() {
+        let GameEvent::EnemyKilled {
+            position,
+            by_player: true,
+            ..
+        } = event
+        else {
+            continue;
+        };
+        if rng.random::<f32>() >= VOLLEY_CHANCE {
+            continue;
+        }
+        for i in 0..VOLLEY_BALLS {
+            let angle = TAU * i as f32 / VOLLEY_BALLS as f32;
+            let ball_transform = Transform::from_translation(position.extend(0.0))
+                .with_rotation(Quat::from_rotation_z(angle - FRAC_PI_2));
+            commands.spawn(cannon_ball(
+                ball_transform,
+                VOLLEY_SPEED * stats.projectile_speed_percentage,
+                VOLLEY_DAMAGE * stats.projectile_damage_percentage,
+                GameCollisionLayer::Enemy,
+                entity,
+                &mut meshes,
+                &mut materials,
+            ));
+        }
+    }
+}
+
+/// Checked whenever the hull changes, and when the relic is found, in case the
+/// hull is already low by then.
+fn last_stand(
+    mut events: MessageReader<GameEvent>,
+    player: Single<(Ref<Relics>, &Health, &mut StatModifiers), With<Player>>,
+) {
+    let (relics, health, mut modifiers) = player.into_inner();
+    let changed = events.read().fold(None, |fraction, event| match *event {
+        GameEvent::PlayerHealthChanged { fraction } => Some(fraction),
+        _ => fraction,
+    });
+    let fraction = match changed {
+        Some(fraction) => fraction,
+        None if relics.is_changed() => health.fraction(),
+        None => return,
+    };
+    if !relics.has(Relic::LastStand) {
+        return;
+    }
+
+    let source = ModifierSource::Relic(Relic::LastStand);
+    let active = modifiers.has_source(&source);
+    if fraction < LAST_STAND_HEALTH && !active {
+        modifiers.add(StatModifier {
+            stat: Stat::ProjectileRate,
+            op: ModifierOp::Multiply(LAST_STAND_RATE),
+            source,
+        });
+    } else if fraction >= LAST_STAND_HEALTH && active {
+        modifiers.remove_source(&source);
+    }
+}
+
+fn skipping_stones(
+    mut commands: Commands,
+    balls: Query<(Entity, &CanonBall), Added<CanonBall>>,
+    players: Query<&Relics, With<Player>>,
+) {
+    for (entity, ball) in &balls {
+        if players
+            .get(ball.source)
+            .is_ok_and(|relics| relics.has(Relic::SkippingStones))
+        {
+            commands.entity(entity).insert(Ricochet { bounces: 1 });
+        }
+    }
+}
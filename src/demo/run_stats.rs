@@ -0,0 +1,352 @@
+//! Statistics of the current run, shown on the game over screen.
+//!
+//! [`RunStats`] is filled in from [`GameEvent`]s and [`Damaged`] messages as
+//! the run goes on, and every few seconds a [`Sample`] of the state of the
+//! fight is taken. When the player's ship sinks, the run is wrapped up, the
+//! game over screen takes over and the stats are written to a JSON file in the
+//! platform's data directory, so runs can be compared when balancing the game.
+
+use std::time::Duration;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Serialize, Serializer};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        Health,
+        damage::{Damaged, Destroyed, apply_damage},
+        enemy::{Enemy, EnemyKind},
+        events::GameEvent,
+        hud::RunTime,
+        loot::PickupKind,
+        player::Player,
+        ships::{HullKind, SelectedHull},
+        terrain::fog::Exploration,
+        upgrades::PickedUpgrades,
+        weapons::WeaponKind,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunSeed>();
+    app.init_resource::<RunStats>();
+    app.init_resource::<StatsSampler>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_stats);
+    #[cfg(not(target_family = "wasm"))]
+    app.add_systems(OnEnter(Screen::GameOver), export_stats);
+    app.add_systems(
+        Update,
+        (record_events, record_damage, take_samples, end_run)
+            .chain()
+            .after(apply_damage)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The seed the world of the next run is generated from.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RunSeed(pub u32);
+
+impl Default for RunSeed {
+    fn default() -> Self {
+        Self(123)
+    }
+}
+
+/// A sample of the run is taken this often.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct WeaponStats {
+    pub shots: u32,
+    pub hits: u32,
+    pub damage: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct EnemyStats {
+    /// Enemies of this kind sunk by the player.
+    pub kills: u32,
+    /// Damage enemies of this kind took, from any source.
+    pub damage_taken: f32,
+}
+
+/// The state of the run at one point in time.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Sample {
+    /// Seconds since the start of the run.
+    pub time: f32,
+    /// Share of the player's hull left.
+    pub hull: f32,
+    pub enemies: u32,
+    /// Damage dealt to enemies per second since the previous sample.
+    pub dps: f32,
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize)]
+pub struct RunStats {
+    pub seed: u32,
+    pub hull: HullKind,
+    #[serde(serialize_with = "as_secs")]
+    pub time_survived: Duration,
+    pub waves_cleared: u32,
+    pub enemies: HashMap<EnemyKind, EnemyStats>,
+    pub weapons: HashMap<WeaponKind, WeaponStats>,
+    /// Pickups collected, by kind.
+    pub pickups: HashMap<PickupKind, u32>,
+    pub gold_earned: u32,
+    pub damage_taken: f32,
+    /// Share of the world the player has seen, between 0.0 and 1.0.
+    pub explored: f32,
+    /// Names of the upgrades taken and how often each was taken.
+    pub upgrades: Vec<(String, u32)>,
+    pub samples: Vec<Sample>,
+}
+
+fn as_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f32(duration.as_secs_f32())
+}
+
+impl RunStats {
+    pub fn kills(&self, kind: EnemyKind) -> u32 {
+        self.enemies.get(&kind).map_or(0, |enemy| enemy.kills)
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.enemies.values().map(|enemy| enemy.kills).sum()
+    }
+
+    /// Share of the shots of all weapons that hit, `None` without any shots.
+    pub fn accuracy(&self) -> Option<f32> {
+        let (shots, hits) = self.weapons.values().fold((0, 0), |(shots, hits), weapon| {
+            (shots + weapon.shots, hits + weapon.hits)
+        });
+        (shots > 0).then(|| hits as f32 / shots as f32)
+    }
+
+    pub fn record(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::EnemyKilled {
+                kind,
+                by_player: true,
+                ..
+            } => self.enemies.entry(kind).or_default().kills += 1,
+            GameEvent::ShotFired { weapon } => self.weapons.entry(weapon).or_default().shots += 1,
+            GameEvent::WeaponHit { weapon } => self.weapons.entry(weapon).or_default().hits += 1,
+            GameEvent::WaveCleared { wave } => self.waves_cleared = self.waves_cleared.max(wave),
+            GameEvent::GoldEarned { amount } => self.gold_earned += amount,
+            GameEvent::PickedUp { kind } => *self.pickups.entry(kind).or_default() += 1,
+            _ => {}
+        }
+    }
+
+    /// Damage after armor and shields, credited to the weapon that dealt it.
+    pub fn record_damage(&mut self, event: &Damaged) {
+        let amount = event.amount as f32;
+        if let Some(weapon) = event.weapon {
+            self.weapons.entry(weapon).or_default().damage += amount;
+        }
+        match event.enemy {
+            Some(kind) => self.enemies.entry(kind).or_default().damage_taken += amount,
+            None => self.damage_taken += amount,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Damage dealt to enemies since the last [`Sample`].
+#[derive(Resource, Debug, Default)]
+struct StatsSampler {
+    timer: Timer,
+    damage: f32,
+}
+
+fn reset_stats(
+    mut stats: ResMut<RunStats>,
+    mut sampler: ResMut<StatsSampler>,
+    seed: Res<RunSeed>,
+    hull: Res<SelectedHull>,
+) {
+    *stats = RunStats {
+        seed: seed.0,
+        hull: hull.0,
+        ..default()
+    };
+    *sampler = StatsSampler {
+        timer: Timer::new(SAMPLE_INTERVAL, TimerMode::Repeating),
+        damage: 0.0,
+    };
+}
+
+fn record_events(mut events: MessageReader<GameEvent>, mut stats: ResMut<RunStats>) {
+    for event in events.read() {
+        stats.record(event);
+    }
+}
+
+fn record_damage(
+    mut damaged: MessageReader<Damaged>,
+    mut stats: ResMut<RunStats>,
+    mut sampler: ResMut<StatsSampler>,
+) {
+    for event in damaged.read() {
+        stats.record_damage(event);
+        if event.enemy.is_some() {
+            sampler.damage += event.amount as f32;
+        }
+    }
+}
+
+fn take_samples(
+    time: Res<Time>,
+    run_time: Res<RunTime>,
+    mut stats: ResMut<RunStats>,
+    mut sampler: ResMut<StatsSampler>,
+    player: Single<&Health, With<Player>>,
+    enemies: Query<(), With<Enemy>>,
+) {
+    sampler.timer.tick(time.delta());
+    if !sampler.timer.just_finished() {
+        return;
+    }
+    stats.samples.push(Sample {
+        time: run_time.0.elapsed_secs(),
+        hull: player.fraction(),
+        enemies: enemies.iter().count() as u32,
+        dps: sampler.damage / SAMPLE_INTERVAL.as_secs_f32(),
+    });
+    sampler.damage = 0.0;
+}
+
+fn end_run(
+    mut destroyed: MessageReader<Destroyed>,
+    players: Query<(), With<Player>>,
+    run_time: Res<RunTime>,
+    picked: Res<PickedUpgrades>,
+    exploration: Res<Exploration>,
+    mut stats: ResMut<RunStats>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if !destroyed.read().any(|event| players.contains(event.entity)) {
+        return;
+    }
+    stats.time_survived = run_time.0.elapsed();
+    stats.explored = exploration.fraction;
+    let mut upgrades: Vec<(String, u32)> = picked
+        .0
+        .iter()
+        .map(|(name, count)| (name.clone(), *count))
+        .collect();
+    upgrades.sort();
+    stats.upgrades = upgrades;
+    next_screen.set(Screen::GameOver);
+}
+
+/// Writes the stats of the run that just ended to `runs/` in the platform's
+/// data directory.
+#[cfg(not(target_family = "wasm"))]
+fn export_stats(stats: Res<RunStats>) {
+    let Some(dir) = dirs::data_dir() else {
+        warn!("Could not write the run stats: no data directory on this platform");
+        return;
+    };
+    let export = || -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        let dir = dir.join(env!("CARGO_PKG_NAME")).join("runs");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("run-{secs}.json"));
+        std::fs::write(&path, stats.to_json()?)?;
+        Ok(path)
+    };
+    match export() {
+        Ok(path) => info!("Run stats written to {}", path.display()),
+        Err(err) => warn!("Could not write the run stats: {err}"),
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::{ecs::entity::Entity, math::Vec2};
+
+    #[allow(unused)]
+    use crate::demo::{
+        damage::{DamageKind, Damaged},
+        enemy::EnemyKind,
+        events::GameEvent,
+        loot::PickupKind,
+        run_stats::RunStats,
+        weapons::WeaponKind,
+    };
+
+    #[test]
+    fn events_add_up() {
+        let mut stats = RunStats::default();
+        assert_eq!(stats.accuracy(), None);
+
+        let kill = |kind, by_player| GameEvent::EnemyKilled {
+            position: Vec2::ZERO,
+            kind,
+            by_player,
+        };
+        for event in [
+            kill(EnemyKind::Raider, true),
+            kill(EnemyKind::Raider, true),
+            kill(EnemyKind::Boss, true),
+            kill(EnemyKind::Raider, false),
+            GameEvent::ShotFired {
+                weapon: WeaponKind::Cannon,
+            },
+            GameEvent::ShotFired {
+                weapon: WeaponKind::Cannon,
+            },
+            GameEvent::ShotFired {
+                weapon: WeaponKind::ChainShot,
+            },
+            GameEvent::ShotFired {
+                weapon: WeaponKind::ChainShot,
+            },
+            GameEvent::WeaponHit {
+                weapon: WeaponKind::Cannon,
+            },
+            GameEvent::WaveCleared { wave: 2 },
+            GameEvent::GoldEarned { amount: 5 },
+            GameEvent::GoldEarned { amount: 7 },
+            GameEvent::PickedUp {
+                kind: PickupKind::Gold,
+            },
+        ] {
+            stats.record(&event);
+        }
+        // Armor soaked up some of the hit.
+        stats.record_damage(&Damaged {
+            entity: Entity::PLACEHOLDER,
+            position: Vec2::ZERO,
+            amount: 24,
+            kind: DamageKind::Cannon,
+            enemy: Some(EnemyKind::Raider),
+            weapon: Some(WeaponKind::Cannon),
+        });
+
+        assert_eq!(stats.kills(EnemyKind::Raider), 2);
+        assert_eq!(stats.total_kills(), 3);
+        assert_eq!(stats.accuracy(), Some(0.25));
+        assert_eq!(stats.weapons[&WeaponKind::Cannon].damage, 24.0);
+        assert_eq!(stats.enemies[&EnemyKind::Raider].damage_taken, 24.0);
+        assert_eq!(stats.waves_cleared, 2);
+        assert_eq!(stats.gold_earned, 12);
+        assert_eq!(stats.pickups[&PickupKind::Gold], 1);
+
+        let json = stats.to_json().unwrap();
+        assert!(json.contains("\"Raider\""));
+        assert!(json.contains("\"time_survived\": 0.0"));
+    }
+}
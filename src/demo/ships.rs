@@ -0,0 +1,188 @@
+//! The hulls the player can choose from before a run.
+//!
+//! A [`ShipHull`] decides the size, handling, toughness and weapon mounts of
+//! the player's ship. The shape of the collider is derived from the shape
+//! that the boat shader draws, so the two always match.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::demo::weapons::WeaponKind;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SelectedHull>();
+}
+
+/// The boat shader draws the hull in a space spanning this far from the center
+/// of the mesh in every direction.
+const SHADER_EXTENT: f32 = 5.0;
+
+pub struct ShipHull {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Size of the mesh the boat shader draws on.
+    pub size: Vec2,
+    /// Half extents of the hull in shader space, see [`SHADER_EXTENT`].
+    pub shape: Vec2,
+    /// How much of the corners of the hull is cut off, in shader space.
+    pub chamfer: f32,
+    /// Width of the sail, relative to the default one.
+    pub sail: f32,
+    pub mass: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub max_speed: f32,
+    pub health: i32,
+    /// Pairs of cannon mounts along the sides, up to three.
+    pub broadside_mounts: usize,
+    /// Whether a weapon can be mounted on the bow.
+    pub bow_mount: bool,
+    /// Weapons mounted along the sides at the start.
+    pub starting_weapons: &'static [WeaponKind],
+    /// The weapon on the bow at the start.
+    pub bow_weapon: Option<WeaponKind>,
+}
+
+impl ShipHull {
+    /// Converts a point from shader space to world space.
+    fn to_world(&self, p: Vec2) -> Vec2 {
+        p / SHADER_EXTENT * self.size / 2.0
+    }
+
+    /// The outline of the hull as drawn by the boat shader.
+    pub fn outline(&self) -> Vec<Vec2> {
+        let Vec2 { x, y } = self.shape;
+        let chamfer = self.chamfer.min(x).min(y);
+        [
+            Vec2::new(x - chamfer, y),
+            Vec2::new(x, y - chamfer),
+            Vec2::new(x, -y + chamfer),
+            Vec2::new(x - chamfer, -y),
+            Vec2::new(-x + chamfer, -y),
+            Vec2::new(-x, -y + chamfer),
+            Vec2::new(-x, y - chamfer),
+            Vec2::new(-x + chamfer, y),
+        ]
+        .into_iter()
+        .map(|p| self.to_world(p))
+        .collect()
+    }
+
+    pub fn collider(&self) -> Collider {
+        let size = self.to_world(self.shape) * 2.0;
+        Collider::convex_hull(self.outline()).unwrap_or(Collider::rectangle(size.x, size.y))
+    }
+
+    /// Parameters of the boat shader: half extents of the hull, chamfer and sail width.
+    pub fn shader_params(&self) -> Vec4 {
+        Vec4::new(self.shape.x, self.shape.y, self.chamfer, self.sail)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Reflect)]
+pub enum HullKind {
+    Sloop,
+    #[default]
+    Brig,
+    Galleon,
+}
+
+impl HullKind {
+    pub const ALL: [HullKind; 3] = [HullKind::Sloop, HullKind::Brig, HullKind::Galleon];
+
+    pub fn hull(self) -> &'static ShipHull {
+        match self {
+            HullKind::Sloop => &SLOOP,
+            HullKind::Brig => &BRIG,
+            HullKind::Galleon => &GALLEON,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            HullKind::Sloop => Color::srgb(0.4, 0.75, 0.9),
+            HullKind::Brig => Color::srgb(0.6, 0.6, 0.6),
+            HullKind::Galleon => Color::srgb(0.85, 0.65, 0.3),
+        }
+    }
+}
+
+const SLOOP: ShipHull = ShipHull {
+    name: "Sloop",
+    description: "Small and quick to turn, but thin-hulled.",
+    size: Vec2::new(240.0, 400.0),
+    shape: Vec2::new(0.6, 1.4),
+    chamfer: 0.6,
+    sail: 0.8,
+    mass: 6.0,
+    linear_damping: 0.15,
+    angular_damping: 1.5,
+    max_speed: 480.0,
+    health: 350,
+    broadside_mounts: 2,
+    bow_mount: true,
+    starting_weapons: &[WeaponKind::Cannon, WeaponKind::Cannon],
+    bow_weapon: Some(WeaponKind::Cannon),
+};
+
+const BRIG: ShipHull = ShipHull {
+    name: "Brig",
+    description: "A balanced warship with three cannons on each side.",
+    size: Vec2::new(300.0, 500.0),
+    shape: Vec2::new(0.7, 1.5),
+    chamfer: 0.7,
+    sail: 1.0,
+    mass: 10.0,
+    linear_damping: 0.2,
+    angular_damping: 2.0,
+    max_speed: 400.0,
+    health: 500,
+    broadside_mounts: 3,
+    bow_mount: true,
+    starting_weapons: &[WeaponKind::Cannon, WeaponKind::Cannon, WeaponKind::Cannon],
+    bow_weapon: Some(WeaponKind::Cannon),
+};
+
+const GALLEON: ShipHull = ShipHull {
+    name: "Galleon",
+    description: "Slow and heavy, with three cannons on each side and a thick hull.",
+    size: Vec2::new(360.0, 600.0),
+    shape: Vec2::new(0.9, 1.6),
+    chamfer: 0.6,
+    sail: 1.3,
+    mass: 16.0,
+    linear_damping: 0.3,
+    angular_damping: 3.0,
+    max_speed: 330.0,
+    health: 800,
+    broadside_mounts: 3,
+    bow_mount: false,
+    starting_weapons: &[WeaponKind::Cannon, WeaponKind::Cannon, WeaponKind::Cannon],
+    bow_weapon: None,
+};
+
+/// The hull the next run starts with.
+#[derive(Resource, Debug, Default)]
+pub struct SelectedHull(pub HullKind);
+
+mod test {
+    #[allow(unused)]
+    use bevy::math::Vec2;
+
+    #[allow(unused)]
+    use crate::demo::ships::HullKind;
+
+    #[test]
+    fn outline_matches_shape() {
+        for kind in HullKind::ALL {
+            let hull = kind.hull();
+            let outline = hull.outline();
+            let max = outline.iter().fold(Vec2::ZERO, |max, p| max.max(p.abs()));
+            let expected = hull.size * hull.shape / 10.0;
+            assert!((max - expected).abs().max_element() < 1e-3);
+            assert!(hull.starting_weapons.len() <= hull.broadside_mounts);
+            assert!(hull.bow_mount || hull.bow_weapon.is_none());
+        }
+    }
+}
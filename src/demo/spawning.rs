@@ -0,0 +1,366 @@
+//! Picking spawn points for enemies.
+//!
+//! Instead of fixed spawners, a spawn point is chosen whenever an enemy is due.
+//! Random candidates in a ring around the player are filtered down to open
+//! water outside of the camera view, and one of them is picked, weighted by the
+//! [`SpawnRegion`]s it lies in and by whether an island hides it from the player.
+//!
+//! The enemy doesn't appear right away. A [`SpawnMarker`] shows a warning
+//! ripple at the chosen point first, and the enemy only emerges once it has
+//! run out and the player isn't sitting on top of it.
+
+use avian2d::prelude::*;
+use bevy::{
+    prelude::*,
+    render::render_resource::AsBindGroup,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        GameCollisionLayer,
+        affixes::{Affixes, roll_affixes},
+        boss::boss,
+        enemy::{EnemyKind, EnemyMaterial, enemy},
+        fire_ship::fire_ship,
+        player::Player,
+        terrain::height::{TerrainChunk, WATER_LEVEL},
+        wave_director::WaveDirector,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(SpawnerConfig::default());
+    app.add_plugins(Material2dPlugin::<SpawnMarkerMaterial>::default());
+    app.add_systems(
+        Update,
+        (spawn_enemies, resolve_spawn_markers)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Resource)]
+pub struct SpawnerConfig {
+    /// Time between two spawns.
+    pub timer: Timer,
+    /// Spawn points are never closer to the player than this, nor inside the camera view.
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// Candidates sampled per spawn.
+    pub candidates: usize,
+    /// Weight multiplier for points that have land between them and the player.
+    pub behind_island_weight: f32,
+}
+
+impl Default for SpawnerConfig {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+            min_distance: 800.0,
+            max_distance: 1800.0,
+            candidates: 24,
+            behind_island_weight: 3.0,
+        }
+    }
+}
+
+/// An area that makes spawn points inside of it more likely.
+#[derive(Component, Debug, Clone)]
+pub struct SpawnRegion {
+    pub area: Rect,
+    pub weight: f32,
+}
+
+/// Margin around the camera view, so enemies don't pop in at the screen border.
+const OFF_SCREEN_MARGIN: f32 = 150.0;
+
+const SPAWN_WARNING_SECS: f32 = 1.5;
+
+/// A pending spawn. Audio cues can hook onto `Added<SpawnMarker>`.
+#[derive(Component)]
+pub struct SpawnMarker {
+    pub kind: EnemyKind,
+    pub timer: Timer,
+    /// Rotation of the enemy once it spawns.
+    pub rotation: Quat,
+}
+
+impl SpawnMarker {
+    fn radius(&self) -> f32 {
+        self.kind.clearance() * 1.3
+    }
+}
+
+/// Warns about an enemy of `kind` spawning at `point`.
+pub fn spawn_marker(
+    kind: EnemyKind,
+    point: Vec2,
+    rotation: Quat,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<SpawnMarkerMaterial>,
+) -> impl Bundle {
+    let marker = SpawnMarker {
+        kind,
+        timer: Timer::from_seconds(SPAWN_WARNING_SECS, TimerMode::Once),
+        rotation,
+    };
+    (
+        Name::new("Spawn Marker"),
+        Mesh2d(meshes.add(Rectangle::from_length(marker.radius() * 2.0))),
+        MeshMaterial2d(materials.add(SpawnMarkerMaterial { time: Vec4::ZERO })),
+        Transform::from_translation(point.extend(0.1)),
+        DespawnOnExit(Screen::Gameplay),
+        marker,
+    )
+}
+
+fn spawn_enemies(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut config: ResMut<SpawnerConfig>,
+    mut director: ResMut<WaveDirector>,
+    player: Single<&Transform, With<Player>>,
+    camera: Single<(&Transform, &Projection), With<Camera2d>>,
+    chunks: Query<(&TerrainChunk, &Transform)>,
+    regions: Query<&SpawnRegion>,
+    spatial_query: SpatialQuery,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<SpawnMarkerMaterial>>,
+) {
+    let Some(&kind) = director.pending.last() else {
+        return;
+    };
+    config.timer.tick(time.delta());
+    if !config.timer.just_finished() {
+        return;
+    }
+
+    let player = player.translation.xy();
+    let (camera_transform, projection) = camera.into_inner();
+    let view_radius = match projection {
+        Projection::Orthographic(orthographic) => orthographic.area.half_size().length(),
+        _ => 0.0,
+    };
+    let view = (
+        camera_transform.translation.xy(),
+        view_radius + OFF_SCREEN_MARGIN,
+    );
+
+    let terrain = SpatialQueryFilter::from_mask(GameCollisionLayer::Terrain);
+    let hull = Collider::circle(kind.clearance());
+    let is_clear = |point| {
+        is_open_water(point, chunks.iter())
+            && spatial_query
+                .shape_intersections(&hull, point, 0.0, &terrain)
+                .is_empty()
+    };
+    let weight = |point: Vec2| {
+        let mut weight = 1.0
+            + regions
+                .iter()
+                .filter(|region| region.area.contains(point))
+                .map(|region| region.weight)
+                .sum::<f32>();
+        if let Ok(direction) = Dir2::new(player - point)
+            && spatial_query
+                .cast_ray(point, direction, point.distance(player), true, &terrain)
+                .is_some()
+        {
+            weight *= config.behind_island_weight;
+        }
+        weight
+    };
+    let Some(point) = pick_spawn_point(&config, player, view, is_clear, weight, &mut rand::rng())
+    else {
+        // Nothing suitable around the player right now, try again on the next tick.
+        return;
+    };
+
+    let to_player = player - point;
+    commands.spawn(spawn_marker(
+        kind,
+        point,
+        Quat::from_rotation_z(to_player.to_angle() - std::f32::consts::FRAC_PI_2),
+        &mut meshes,
+        &mut materials,
+    ));
+    director.pending.pop();
+}
+
+fn resolve_spawn_markers(
+    time: Res<Time>,
+    mut commands: Commands,
+    player: Single<&Transform, With<Player>>,
+    mut markers: Query<(
+        Entity,
+        &mut SpawnMarker,
+        &Transform,
+        &MeshMaterial2d<SpawnMarkerMaterial>,
+    )>,
+    mut marker_materials: ResMut<Assets<SpawnMarkerMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<EnemyMaterial>>,
+    director: Res<WaveDirector>,
+) {
+    let mut rng = rand::rng();
+    let player = player.translation.xy();
+    for (entity, mut marker, transform, material) in &mut markers {
+        let position = transform.translation.xy();
+        let blocked = position.distance(player) < marker.radius();
+        if !blocked {
+            marker.timer.tick(time.delta());
+        }
+        if let Some(m) = marker_materials.get_mut(material.0.id()) {
+            m.time = Vec4::new(time.elapsed_secs(), marker.timer.fraction(), 0.0, 0.0);
+        }
+
+        if marker.timer.is_finished() {
+            let transform =
+                Transform::from_translation(position.extend(0.0)).with_rotation(marker.rotation);
+            let mut spawned = match marker.kind {
+                EnemyKind::Raider => commands.spawn(enemy(transform, &mut meshes, &mut materials)),
+                EnemyKind::FireShip => {
+                    commands.spawn(fire_ship(transform, &mut meshes, &mut materials))
+                }
+                EnemyKind::Boss => commands.spawn(boss(transform, &mut meshes, &mut materials)),
+            };
+            if marker.kind != EnemyKind::Boss {
+                spawned.insert(Affixes(roll_affixes(director.wave, &mut rng)));
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+struct Candidate {
+    point: Vec2,
+    weight: f32,
+}
+
+/// Samples candidates around the player, outside of the view. `is_clear`
+/// tells whether a ship fits at a point, `weight` how likely it spawns there.
+fn pick_spawn_point(
+    config: &SpawnerConfig,
+    player: Vec2,
+    (view_center, view_radius): (Vec2, f32),
+    is_clear: impl Fn(Vec2) -> bool,
+    weight: impl Fn(Vec2) -> f32,
+    rng: &mut impl Rng,
+) -> Option<Vec2> {
+    let min_distance = config.min_distance.min(config.max_distance);
+
+    let mut candidates = Vec::with_capacity(config.candidates);
+    for _ in 0..config.candidates {
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        let distance = rng.random_range(min_distance..=config.max_distance);
+        let point = player + Vec2::from_angle(angle) * distance;
+
+        if point.distance(view_center) < view_radius || !is_clear(point) {
+            continue;
+        }
+        candidates.push(Candidate {
+            point,
+            weight: weight(point),
+        });
+    }
+
+    candidates
+        .choose_weighted(rng, |c| c.weight)
+        .ok()
+        .map(|c| c.point)
+}
+
+fn is_open_water<'a>(
+    point: Vec2,
+    chunks: impl IntoIterator<Item = (&'a TerrainChunk, &'a Transform)>,
+) -> bool {
+    chunks.into_iter().any(|(chunk, transform)| {
+        chunk
+            .height_at(point - transform.translation.xy())
+            .is_some_and(|height| height < WATER_LEVEL)
+    })
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct SpawnMarkerMaterial {
+    /// x: time, y: spawn progress
+    #[uniform(0)]
+    time: Vec4,
+}
+
+const SPAWN_MARKER_SHADER_PATH: &str = "shaders/spawn_marker.wesl";
+
+impl Material2d for SpawnMarkerMaterial {
+    fn vertex_shader() -> bevy::shader::ShaderRef {
+        bevy::shader::ShaderRef::Default
+    }
+
+    fn fragment_shader() -> bevy::shader::ShaderRef {
+        SPAWN_MARKER_SHADER_PATH.into()
+    }
+
+    fn depth_bias(&self) -> f32 {
+        0.0
+    }
+
+    fn alpha_mode(&self) -> bevy::sprite_render::AlphaMode2d {
+        bevy::sprite_render::AlphaMode2d::Blend
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::{math::Vec2, transform::components::Transform};
+    #[allow(unused)]
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[allow(unused)]
+    use crate::demo::{
+        spawning::{SpawnerConfig, is_open_water, pick_spawn_point},
+        terrain::height::{SQUARE, TerrainChunk, WATER_LEVEL},
+    };
+
+    #[test]
+    fn spawns_in_open_water_out_of_view() {
+        // Land on the western half of the map, open water on the eastern half.
+        let mut terrain = TerrainChunk::zero();
+        for y in 0..SQUARE {
+            for x in 0..SQUARE / 2 {
+                terrain.set(x, y, 1.0);
+            }
+        }
+        assert_eq!(terrain.height_at(Vec2::new(-100.0, 0.0)), Some(1.0));
+        assert_eq!(terrain.height_at(Vec2::new(100.0, 0.0)), Some(0.0));
+        assert_eq!(terrain.height_at(Vec2::new(2048.0, 0.0)), None);
+        assert_eq!(terrain.height_at(Vec2::new(0.0, -2049.0)), None);
+
+        let transform = Transform::default();
+        let chunks = [(&terrain, &transform)];
+        let config = SpawnerConfig::default();
+        let player = Vec2::new(1000.0, 0.0);
+        let view = (player, 900.0);
+        let mut rng = StdRng::seed_from_u64(27);
+        let mut picked = 0;
+        for _ in 0..200 {
+            let Some(point) = pick_spawn_point(
+                &config,
+                player,
+                view,
+                |point| is_open_water(point, chunks),
+                |_| 1.0,
+                &mut rng,
+            ) else {
+                continue;
+            };
+            picked += 1;
+            assert!(terrain.height_at(point).is_some_and(|h| h < WATER_LEVEL));
+            assert!(point.distance(view.0) >= view.1);
+        }
+        assert!(picked > 100);
+    }
+}
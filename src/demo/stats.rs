@@ -0,0 +1,188 @@
+//! Layered player stats.
+//!
+//! The effective [`PlayerStats`] of a ship are recalculated from its
+//! [`StatModifiers`] whenever they change: every stat starts at its base value,
+//! then all additive modifiers are summed up, then the result is multiplied by
+//! all multiplicative ones. Each modifier is tagged with its
+//! [`ModifierSource`], so everything it granted can be taken away again.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{player::PlayerStats, relics::Relic, weapons::WeaponSlots},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        recalculate_stats
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Reflect)]
+pub enum Stat {
+    ProjectileDamage,
+    ProjectileSpeed,
+    /// Multiplier of reload times, lower is faster.
+    ProjectileRate,
+    ExplosionDamage,
+    RamDamage,
+    PickupRadius,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Reflect)]
+pub enum ModifierOp {
+    Add(f32),
+    Multiply(f32),
+}
+
+/// Where a modifier came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect)]
+pub enum ModifierSource {
+    /// Tuning that every player ship starts with.
+    Ship,
+    Upgrade(String),
+    Relic(Relic),
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub op: ModifierOp,
+    pub source: ModifierSource,
+}
+
+/// Effective stats never drop below this, so nothing ends up with zero reload time.
+const MIN_STAT: f32 = 0.05;
+
+impl PlayerStats {
+    fn get_mut(&mut self, stat: Stat) -> &mut f32 {
+        match stat {
+            Stat::ProjectileDamage => &mut self.projectile_damage_percentage,
+            Stat::ProjectileSpeed => &mut self.projectile_speed_percentage,
+            Stat::ProjectileRate => &mut self.projectile_rate_percentage,
+            Stat::ExplosionDamage => &mut self.explosion_damage_percentage,
+            Stat::RamDamage => &mut self.ram_damage_percentage,
+            Stat::PickupRadius => &mut self.pickup_radius,
+        }
+    }
+}
+
+/// The base stats of a ship and everything modifying them.
+#[derive(Component, Debug, Clone)]
+pub struct StatModifiers {
+    pub base: PlayerStats,
+    modifiers: Vec<StatModifier>,
+}
+
+impl StatModifiers {
+    pub fn new(base: PlayerStats) -> StatModifiers {
+        StatModifiers {
+            base,
+            modifiers: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, modifier: StatModifier) {
+        self.modifiers.push(modifier);
+    }
+
+    pub fn has_source(&self, source: &ModifierSource) -> bool {
+        self.modifiers
+            .iter()
+            .any(|modifier| modifier.source == *source)
+    }
+
+    /// Removes all modifiers from `source`.
+    pub fn remove_source(&mut self, source: &ModifierSource) {
+        self.modifiers.retain(|modifier| modifier.source != *source);
+    }
+
+    pub fn effective(&self) -> PlayerStats {
+        let mut stats = self.base.clone();
+        for stat in [
+            Stat::ProjectileDamage,
+            Stat::ProjectileSpeed,
+            Stat::ProjectileRate,
+            Stat::ExplosionDamage,
+            Stat::RamDamage,
+            Stat::PickupRadius,
+        ] {
+            let modifiers = self.modifiers.iter().filter(|m| m.stat == stat);
+            let mut added = 0.0;
+            let mut multiplied = 1.0;
+            for modifier in modifiers {
+                match modifier.op {
+                    ModifierOp::Add(amount) => added += amount,
+                    ModifierOp::Multiply(factor) => multiplied *= factor,
+                }
+            }
+            let value = stats.get_mut(stat);
+            *value = ((*value + added) * multiplied).max(MIN_STAT);
+        }
+        stats
+    }
+}
+
+fn recalculate_stats(
+    mut ships: Query<
+        (&StatModifiers, &mut PlayerStats, Option<&mut WeaponSlots>),
+        Changed<StatModifiers>,
+    >,
+) {
+    for (modifiers, mut stats, slots) in &mut ships {
+        let effective = modifiers.effective();
+        if effective.projectile_rate_percentage != stats.projectile_rate_percentage
+            && let Some(mut slots) = slots
+        {
+            for weapon in slots.weapons_mut() {
+                weapon.set_rate(effective.projectile_rate_percentage);
+            }
+        }
+        *stats = effective;
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::{
+        player::PlayerStats,
+        stats::{ModifierOp, ModifierSource, Stat, StatModifier, StatModifiers},
+    };
+
+    #[test]
+    fn modifier_layers() {
+        let mut modifiers = StatModifiers::new(PlayerStats::default());
+        let source = ModifierSource::Upgrade("a".into());
+        modifiers.add(StatModifier {
+            stat: Stat::ProjectileDamage,
+            op: ModifierOp::Multiply(2.0),
+            source: source.clone(),
+        });
+        modifiers.add(StatModifier {
+            stat: Stat::ProjectileDamage,
+            op: ModifierOp::Add(0.5),
+            source: ModifierSource::Upgrade("b".into()),
+        });
+        modifiers.add(StatModifier {
+            stat: Stat::ProjectileRate,
+            op: ModifierOp::Add(-5.0),
+            source: source.clone(),
+        });
+
+        let stats = modifiers.effective();
+        // (1.0 + 0.5) * 2.0
+        assert_eq!(stats.projectile_damage_percentage, 3.0);
+        assert_eq!(stats.projectile_rate_percentage, 0.05);
+        assert_eq!(stats.projectile_speed_percentage, 1.0);
+
+        modifiers.remove_source(&source);
+        let stats = modifiers.effective();
+        assert_eq!(stats.projectile_damage_percentage, 1.5);
+        assert_eq!(stats.projectile_rate_percentage, 1.0);
+    }
+}
@@ -0,0 +1,348 @@
+//! Over-time and control effects on ships.
+//!
+//! Anything can put an effect on a ship by writing an [`ApplyStatus`] message:
+//! weapons on hit, explosions, hazards or enemy abilities. The effects of a
+//! ship are kept in its [`StatusEffects`], where a new effect of a kind that is
+//! already active is merged according to the [`StackRule`] of that kind.
+//!
+//! Tick damage is routed through [`DamageEvent`]s, movement penalties are
+//! written into [`MovementController::speed_multiplier`].
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        damage::{DamageEvent, DamageKind, apply_damage},
+        movement::MovementController,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<ApplyStatus>();
+    app.add_systems(
+        Update,
+        (
+            receive_status_effects,
+            tick_status_effects.before(apply_damage),
+            apply_movement_modifiers,
+            update_status_indicators,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Time between two damage ticks of damaging effects.
+const TICK_SECS: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum StatusEffectKind {
+    /// Damage over time. `strength` is damage per second per stack.
+    Burning,
+    /// Slower movement. `strength` is the share of speed that is lost.
+    Slowed,
+    /// No movement at all.
+    Stunned,
+    /// A holed hull, damage over time and a little slower per stack.
+    /// `strength` is damage per second per stack.
+    Leaking,
+}
+
+/// How a new effect is merged into an active effect of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StackRule {
+    /// Restarts the duration, keeping the stronger of both.
+    Refresh,
+    /// Restarts the duration and adds a stack, up to `max` stacks.
+    Intensify { max: u32 },
+    /// Adds the new duration to the remaining one.
+    Extend,
+}
+
+impl StatusEffectKind {
+    pub fn stack_rule(self) -> StackRule {
+        match self {
+            StatusEffectKind::Burning => StackRule::Intensify { max: 3 },
+            StatusEffectKind::Slowed => StackRule::Refresh,
+            StatusEffectKind::Stunned => StackRule::Extend,
+            StatusEffectKind::Leaking => StackRule::Intensify { max: 5 },
+        }
+    }
+
+    fn damage_kind(self) -> Option<DamageKind> {
+        match self {
+            StatusEffectKind::Burning => Some(DamageKind::Burning),
+            StatusEffectKind::Leaking => Some(DamageKind::Leaking),
+            StatusEffectKind::Slowed | StatusEffectKind::Stunned => None,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            StatusEffectKind::Burning => Color::srgb(0.95, 0.45, 0.1),
+            StatusEffectKind::Slowed => Color::srgb(0.55, 0.8, 0.95),
+            StatusEffectKind::Stunned => Color::srgb(0.95, 0.9, 0.3),
+            StatusEffectKind::Leaking => Color::srgb(0.2, 0.35, 0.8),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    pub remaining: Timer,
+    pub strength: f32,
+    pub stacks: u32,
+    /// Credited with the damage of the effect.
+    pub source: Option<Entity>,
+}
+
+impl StatusEffect {
+    pub fn new(
+        kind: StatusEffectKind,
+        duration: Duration,
+        strength: f32,
+        source: Option<Entity>,
+    ) -> StatusEffect {
+        StatusEffect {
+            kind,
+            remaining: Timer::new(duration, TimerMode::Once),
+            strength,
+            stacks: 1,
+            source,
+        }
+    }
+
+    /// Share of its speed a ship keeps under this effect.
+    fn speed_multiplier(&self) -> f32 {
+        match self.kind {
+            StatusEffectKind::Burning => 1.0,
+            StatusEffectKind::Slowed => 1.0 - self.strength.clamp(0.0, 1.0),
+            StatusEffectKind::Stunned => 0.0,
+            StatusEffectKind::Leaking => 1.0 - 0.05 * self.stacks as f32,
+        }
+    }
+
+    fn merge(&mut self, new: StatusEffect) {
+        match self.kind.stack_rule() {
+            StackRule::Refresh => {
+                self.strength = self.strength.max(new.strength);
+                self.remaining = new.remaining;
+            }
+            StackRule::Intensify { max } => {
+                self.stacks = (self.stacks + new.stacks).min(max);
+                self.strength = self.strength.max(new.strength);
+                self.remaining = new.remaining;
+            }
+            StackRule::Extend => {
+                let remaining = self.remaining.remaining() + new.remaining.remaining();
+                self.remaining = Timer::new(remaining, TimerMode::Once);
+            }
+        }
+        if new.source.is_some() {
+            self.source = new.source;
+        }
+    }
+}
+
+/// All active effects on a ship, at most one per kind.
+#[derive(Component, Debug, Clone)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+    tick: Timer,
+}
+
+impl Default for StatusEffects {
+    fn default() -> Self {
+        Self {
+            effects: Vec::new(),
+            tick: Timer::from_seconds(TICK_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        match self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            Some(active) => active.merge(effect),
+            None => self.effects.push(effect),
+        }
+    }
+
+    pub fn get(&self, kind: StatusEffectKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|e| e.kind == kind)
+    }
+
+    pub fn get_mut(&mut self, kind: StatusEffectKind) -> Option<&mut StatusEffect> {
+        self.effects.iter_mut().find(|e| e.kind == kind)
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(StatusEffect::speed_multiplier)
+            .product::<f32>()
+            .max(0.0)
+    }
+}
+
+/// Projectiles and hazards with this component put the effect on the ships they hit.
+#[derive(Component, Debug, Clone)]
+pub struct InflictsStatus(pub StatusEffect);
+
+/// Puts `effect` on the `target` ship.
+#[derive(Message, Debug, Clone)]
+pub struct ApplyStatus {
+    pub target: Entity,
+    pub effect: StatusEffect,
+}
+
+fn receive_status_effects(
+    mut commands: Commands,
+    mut messages: MessageReader<ApplyStatus>,
+    mut ships: Query<&mut StatusEffects>,
+) {
+    // Ships without effects yet get their component at the end, so several
+    // effects hitting them in the same frame still stack.
+    let mut new: Vec<(Entity, StatusEffects)> = Vec::new();
+    for ApplyStatus { target, effect } in messages.read() {
+        if let Ok(mut effects) = ships.get_mut(*target) {
+            effects.apply(effect.clone());
+        } else if let Some((_, effects)) = new.iter_mut().find(|(e, _)| e == target) {
+            effects.apply(effect.clone());
+        } else {
+            let mut effects = StatusEffects::default();
+            effects.apply(effect.clone());
+            new.push((*target, effects));
+        }
+    }
+    for (target, effects) in new {
+        commands.entity(target).try_insert(effects);
+    }
+}
+
+fn tick_status_effects(
+    time: Res<Time>,
+    mut ships: Query<(Entity, &mut StatusEffects)>,
+    mut damage: MessageWriter<DamageEvent>,
+) {
+    for (entity, mut status) in &mut ships {
+        status.tick.tick(time.delta());
+        let ticks = status.tick.times_finished_this_tick();
+        for effect in &mut status.effects {
+            effect.remaining.tick(time.delta());
+            if ticks == 0 {
+                continue;
+            }
+            if let Some(kind) = effect.kind.damage_kind() {
+                damage.write(DamageEvent {
+                    target: entity,
+                    amount: effect.strength * effect.stacks as f32 * TICK_SECS * ticks as f32,
+                    kind,
+                    source: effect.source,
+                    position: None,
+                    weapon: None,
+                });
+            }
+        }
+        status
+            .effects
+            .retain(|effect| !effect.remaining.is_finished());
+    }
+}
+
+fn apply_movement_modifiers(mut ships: Query<(&StatusEffects, &mut MovementController)>) {
+    for (status, mut controller) in &mut ships {
+        controller.speed_multiplier = status.speed_multiplier();
+    }
+}
+
+/// A small marker above a ship, one per active effect.
+#[derive(Component)]
+struct StatusIndicator;
+
+const INDICATOR_SIZE: f32 = 14.0;
+const INDICATOR_OFFSET: f32 = 80.0;
+
+fn update_status_indicators(
+    mut commands: Commands,
+    ships: Query<(Entity, &StatusEffects, &Transform, Option<&Children>)>,
+    mut indicators: Query<&mut Transform, (With<StatusIndicator>, Without<StatusEffects>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (ship, status, transform, children) in &ships {
+        let existing: Vec<Entity> = children
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|child| indicators.contains(*child))
+            .collect();
+
+        if existing.len() != status.effects.len() {
+            for indicator in existing {
+                commands.entity(indicator).despawn();
+            }
+            let mesh = meshes.add(Circle::new(INDICATOR_SIZE / 2.0));
+            for effect in &status.effects {
+                commands.entity(ship).with_child((
+                    Name::new("Status Indicator"),
+                    StatusIndicator,
+                    Mesh2d(mesh.clone()),
+                    MeshMaterial2d(materials.add(effect.kind.color())),
+                    Transform::default(),
+                ));
+            }
+            continue;
+        }
+
+        // Keep the markers in an upright row above the ship, whichever way it
+        // is facing.
+        let inverse = transform.rotation.inverse();
+        let width = (existing.len() as f32 - 1.0) * INDICATOR_SIZE * 1.5;
+        for (i, indicator) in existing.into_iter().enumerate() {
+            let Ok(mut indicator) = indicators.get_mut(indicator) else {
+                continue;
+            };
+            let offset = Vec3::new(
+                i as f32 * INDICATOR_SIZE * 1.5 - width / 2.0,
+                INDICATOR_OFFSET,
+                1.0,
+            );
+            indicator.translation = inverse * offset;
+            indicator.rotation = inverse;
+        }
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use std::time::Duration;
+
+    #[allow(unused)]
+    use crate::demo::status_effects::{StatusEffect, StatusEffectKind, StatusEffects};
+
+    #[test]
+    fn stacking_rules() {
+        let effect = |kind, secs| StatusEffect::new(kind, Duration::from_secs(secs), 0.5, None);
+        let mut status = StatusEffects::default();
+        for _ in 0..5 {
+            status.apply(effect(StatusEffectKind::Burning, 2));
+        }
+        status.apply(effect(StatusEffectKind::Stunned, 1));
+        status.apply(effect(StatusEffectKind::Stunned, 2));
+        status.apply(effect(StatusEffectKind::Slowed, 1));
+
+        assert_eq!(status.effects.len(), 3);
+        assert_eq!(status.get(StatusEffectKind::Burning).unwrap().stacks, 3);
+        let stun = status.get(StatusEffectKind::Stunned).unwrap();
+        assert_eq!(stun.remaining.remaining(), Duration::from_secs(3));
+        assert_eq!(status.speed_multiplier(), 0.0);
+    }
+}
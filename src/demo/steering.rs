@@ -0,0 +1,231 @@
+//! Local steering for groups of ships.
+//!
+//! Each ship with a [`Steering`] component gets a `seek` direction from its
+//! behavior (e.g. chase the player). Separation and alignment with nearby
+//! ships, and avoidance of terrain ahead, are blended on top of that and
+//! written into the [`MovementController`] intents.
+//!
+//! Neighbors are looked up through a [`SpatialGrid`] that is rebuilt every
+//! frame, so the cost stays linear in the number of ships.
+
+use avian2d::prelude::*;
+use bevy::{math::ops::atan2, platform::collections::HashMap, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{GameCollisionLayer, forward_vec, movement::MovementController},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(SpatialGrid::new(GRID_CELL_SIZE));
+    app.add_systems(
+        Update,
+        (update_spatial_grid, apply_steering)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Should be at least as large as the biggest neighbor radius used by [`Steering`].
+const GRID_CELL_SIZE: f32 = 250.0;
+
+/// Steering parameters of a single ship.
+#[derive(Component, Debug, Clone)]
+pub struct Steering {
+    /// Direction the ship wants to go, set by its behavior.
+    pub seek: Vec2,
+    /// Full forward intent, scaled down while turning.
+    pub thrust: f32,
+    /// Ships closer than this push each other apart.
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    /// Ships closer than this try to match their headings.
+    pub alignment_radius: f32,
+    pub alignment_weight: f32,
+    /// Radius of the circle that is cast ahead of the ship to find terrain.
+    pub avoidance_radius: f32,
+    /// How far ahead the avoidance cast reaches.
+    pub avoidance_distance: f32,
+    pub avoidance_weight: f32,
+}
+
+impl Default for Steering {
+    fn default() -> Self {
+        Self {
+            seek: Vec2::ZERO,
+            thrust: 10.0,
+            separation_radius: 200.0,
+            separation_weight: 1.5,
+            alignment_radius: 250.0,
+            alignment_weight: 0.4,
+            avoidance_radius: 60.0,
+            avoidance_distance: 400.0,
+            avoidance_weight: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GridEntry {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+/// Uniform grid bucketing steering ships by position.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<GridEntry>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> SpatialGrid {
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    fn cell(&self, p: Vec2) -> IVec2 {
+        (p / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Keeps the allocated buckets around, so rebuilding every frame is cheap.
+    pub fn clear(&mut self) {
+        for entries in self.cells.values_mut() {
+            entries.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entry: GridEntry) {
+        let cell = self.cell(entry.position);
+        self.cells.entry(cell).or_default().push(entry);
+    }
+
+    /// All entries within `radius` of `p`, including an entry at `p` itself.
+    pub fn neighbors(&self, p: Vec2, radius: f32) -> impl Iterator<Item = &GridEntry> {
+        let min = self.cell(p - Vec2::splat(radius));
+        let max = self.cell(p + Vec2::splat(radius));
+        let radius_squared = radius * radius;
+
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |e| e.position.distance_squared(p) <= radius_squared)
+    }
+}
+
+fn update_spatial_grid(
+    mut grid: ResMut<SpatialGrid>,
+    ships: Query<(Entity, &Transform, &LinearVelocity), With<Steering>>,
+) {
+    grid.clear();
+    for (entity, transform, velocity) in &ships {
+        grid.insert(GridEntry {
+            entity,
+            position: transform.translation.xy(),
+            velocity: velocity.0,
+        });
+    }
+}
+
+pub fn apply_steering(
+    grid: Res<SpatialGrid>,
+    spatial_query: SpatialQuery,
+    mut ships: Query<(Entity, &Steering, &Transform, &mut MovementController)>,
+) {
+    let terrain = SpatialQueryFilter::from_mask(GameCollisionLayer::Terrain);
+
+    for (entity, steering, transform, mut controller) in &mut ships {
+        let position = transform.translation.xy();
+        let forward = forward_vec(*transform);
+
+        let mut separation = Vec2::ZERO;
+        let mut heading = Vec2::ZERO;
+        let neighbor_radius = steering.separation_radius.max(steering.alignment_radius);
+        for other in grid.neighbors(position, neighbor_radius) {
+            if other.entity == entity {
+                continue;
+            }
+            let away = position - other.position;
+            let distance = away.length();
+            if distance < steering.separation_radius && distance > f32::EPSILON {
+                // Closer neighbors push harder.
+                separation += away / distance * (1.0 - distance / steering.separation_radius);
+            }
+            if distance < steering.alignment_radius {
+                heading += other.velocity.normalize_or_zero();
+            }
+        }
+
+        let mut avoidance = Vec2::ZERO;
+        if let Ok(direction) = Dir2::new(forward)
+            && let Some(hit) = spatial_query.cast_shape(
+                &Collider::circle(steering.avoidance_radius),
+                position,
+                0.0,
+                direction,
+                &ShapeCastConfig::from_max_distance(steering.avoidance_distance),
+                &terrain,
+            )
+        {
+            let closeness = 1.0 - hit.distance / steering.avoidance_distance;
+            avoidance = hit.normal1 * closeness;
+        }
+
+        let desired = steering.seek.normalize_or_zero()
+            + separation * steering.separation_weight
+            + heading.normalize_or_zero() * steering.alignment_weight
+            + avoidance * steering.avoidance_weight;
+        let Some(desired) = desired.try_normalize() else {
+            controller.intent = 0.0;
+            controller.rotation_intent = 0.0;
+            continue;
+        };
+
+        // Ease off the throttle while turning, so packs fan out instead of
+        // overshooting each other.
+        controller.intent = steering.thrust * (0.5 + 0.5 * desired.dot(forward)).max(0.2);
+        let a = desired;
+        let b = forward;
+        controller.rotation_intent = -atan2(a.x * b.y - a.y * b.x, a.x * b.x + a.y * b.y);
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::prelude::*;
+
+    #[allow(unused)]
+    use crate::demo::steering::{GridEntry, SpatialGrid};
+
+    #[test]
+    fn grid_neighbors() {
+        let mut grid = SpatialGrid::new(100.0);
+        let positions = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(50.0, 0.0),
+            Vec2::new(-150.0, 0.0),
+            Vec2::new(0.0, 400.0),
+        ];
+        for (i, position) in positions.into_iter().enumerate() {
+            grid.insert(GridEntry {
+                entity: Entity::from_raw_u32(i as u32 + 1).unwrap(),
+                position,
+                velocity: Vec2::ZERO,
+            });
+        }
+
+        assert_eq!(grid.neighbors(Vec2::ZERO, 100.0).count(), 2);
+        assert_eq!(grid.neighbors(Vec2::ZERO, 200.0).count(), 3);
+        assert_eq!(grid.neighbors(Vec2::new(0.0, 350.0), 100.0).count(), 1);
+
+        grid.clear();
+        assert_eq!(grid.neighbors(Vec2::ZERO, 1000.0).count(), 0);
+    }
+}
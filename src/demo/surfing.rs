@@ -0,0 +1,145 @@
+//! Riding the waves.
+//!
+//! Waves roll in towards the shore, against the direction returned by
+//! [`Waves::wave_height`]. Sailing along with a rising wave fills the surf
+//! meter of a ship, which speeds it up and makes its rams hit harder.
+//! Sailing against the waves drains the meter and slows the ship down.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{forward_vec, movement::MovementController, player::Player, terrain::waves::Waves},
+    screens::Screen,
+    theme::{palette::SURF_BAR, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_surf_meter);
+    app.add_systems(
+        Update,
+        (ride_waves, update_surf_meter)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Headings closer than this (as cosine) to the wave count as riding it.
+const SURF_ALIGNMENT: f32 = 0.5;
+const SURF_GAIN_PER_SEC: f32 = 0.35;
+const SURF_DECAY_PER_SEC: f32 = 0.25;
+const AGAINST_DECAY_PER_SEC: f32 = 0.8;
+/// Extra share of speed at a full meter.
+const SURF_MAX_BONUS: f32 = 0.75;
+/// Share of speed lost when heading straight into the waves.
+const AGAINST_PENALTY: f32 = 0.3;
+
+/// How well a ship is riding the waves.
+#[derive(Component, Debug, Default)]
+pub struct Surf {
+    /// Fills up while surfing, between 0.0 and 1.0.
+    pub meter: f32,
+}
+
+/// Advances the surf meter by `delta` seconds and returns the resulting speed
+/// multiplier. `alignment` is the cosine between heading and wave direction.
+pub fn surf_step(meter: &mut f32, alignment: f32, rising: bool, delta: f32) -> f32 {
+    if rising && alignment > SURF_ALIGNMENT {
+        *meter += SURF_GAIN_PER_SEC * alignment * delta;
+    } else if alignment < -SURF_ALIGNMENT {
+        *meter -= AGAINST_DECAY_PER_SEC * delta;
+    } else {
+        *meter -= SURF_DECAY_PER_SEC * delta;
+    }
+    *meter = meter.clamp(0.0, 1.0);
+
+    let penalty = if rising && alignment < 0.0 {
+        AGAINST_PENALTY * -alignment
+    } else {
+        0.0
+    };
+    (1.0 + *meter * SURF_MAX_BONUS) * (1.0 - penalty)
+}
+
+fn ride_waves(
+    time: Res<Time>,
+    waves: Single<&Waves>,
+    mut ships: Query<(&mut Surf, &mut MovementController, &Transform)>,
+) {
+    for (mut surf, mut controller, transform) in &mut ships {
+        let (wave_dir, _wave_height, rising) =
+            waves.wave_height(transform.translation.xy(), time.elapsed_secs());
+        // Calm water, away from any shore.
+        let alignment = if wave_dir.is_nan() {
+            0.0
+        } else {
+            forward_vec(*transform).dot(-wave_dir)
+        };
+        controller.wave_multiplier = surf_step(
+            &mut surf.meter,
+            alignment,
+            rising && !wave_dir.is_nan(),
+            time.delta_secs(),
+        );
+    }
+}
+
+#[derive(Component)]
+struct SurfMeter;
+
+fn spawn_surf_meter(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Surf Meter"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(16),
+            right: px(16),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: px(4),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            widget::label("Surf"),
+            (widget::bar("Surf", px(180), px(12), SURF_BAR), SurfMeter),
+        ],
+    ));
+}
+
+fn update_surf_meter(
+    surf: Single<&Surf, With<Player>>,
+    mut bar: Single<&mut Bar, With<SurfMeter>>,
+) {
+    if bar.0 != surf.meter {
+        bar.0 = surf.meter;
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::surfing::surf_step;
+
+    #[test]
+    fn surfing_stacks_and_drains() {
+        let mut meter = 0.0;
+        let mut multiplier = 1.0;
+        for _ in 0..10 {
+            multiplier = surf_step(&mut meter, 1.0, true, 0.5);
+        }
+        assert_eq!(meter, 1.0);
+        assert_eq!(multiplier, 1.75);
+
+        // Heading into a rising wave.
+        let multiplier = surf_step(&mut meter, -1.0, true, 0.5);
+        assert!((meter - 0.6).abs() < 1e-6);
+        assert!(multiplier < 1.1);
+
+        // Falling waves don't build up the meter.
+        let mut meter = 0.0;
+        assert_eq!(surf_step(&mut meter, 1.0, false, 1.0), 1.0);
+    }
+}
@@ -0,0 +1,195 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        events::GameEvent,
+        loot::PlayerProgress,
+        player::Player,
+        terrain::height::{CHUNK_SIZE_PIXELS, SQUARE, TerrainMaterial},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Exploration>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_exploration);
+    app.add_systems(
+        Update,
+        (explore, update_fog_textures, reward_exploration)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Cells closer than this to the player's ship are explored.
+const VISION_RADIUS: f32 = 1000.0;
+/// Every time this share of the world is explored, the player is rewarded.
+const MILESTONE: f32 = 0.25;
+const MILESTONE_GOLD: u32 = 50;
+const MILESTONE_XP: u32 = 100;
+
+/// Which cells of the [`TerrainChunk`](super::height::TerrainChunk) on the
+/// same entity the player has seen, one bit per cell.
+#[derive(Component, Debug, Clone)]
+pub struct ExploredCells {
+    bits: Vec<u64>,
+}
+
+impl ExploredCells {
+    pub fn new() -> ExploredCells {
+        ExploredCells {
+            bits: vec![0; (SQUARE * SQUARE).div_ceil(64)],
+        }
+    }
+
+    /// assumes x and y 0..SQUARE
+    pub fn is_explored(&self, x: usize, y: usize) -> bool {
+        let i = y * SQUARE + x;
+        self.bits[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Returns whether the cell wasn't explored before.
+    pub fn explore(&mut self, x: usize, y: usize) -> bool {
+        let i = y * SQUARE + x;
+        let new = !self.is_explored(x, y);
+        self.bits[i / 64] |= 1 << (i % 64);
+        new
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|bits| bits.count_ones() as usize)
+            .sum()
+    }
+
+    /// One byte per cell, 255 where explored, for the terrain shader.
+    pub fn as_tex(&self) -> Image {
+        let bytes = (0..SQUARE)
+            .flat_map(|y| (0..SQUARE).map(move |x| (x, y)))
+            .map(|(x, y)| if self.is_explored(x, y) { 255 } else { 0 })
+            .collect();
+        let mut i = Image::new(
+            Extent3d {
+                width: SQUARE as u32,
+                height: SQUARE as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            bytes,
+            TextureFormat::R8Unorm,
+            RenderAssetUsages::all(),
+        );
+        i.sampler = ImageSampler::nearest();
+
+        i
+    }
+}
+
+/// The center of a cell, relative to the center of its chunk.
+pub fn cell_center(x: usize, y: usize) -> Vec2 {
+    let cell_size = (CHUNK_SIZE_PIXELS / SQUARE) as f32;
+    (Vec2::new(x as f32, y as f32) + 0.5) * cell_size - CHUNK_SIZE_PIXELS as f32 / 2.0
+}
+
+/// How much of the world the player has seen in the current run.
+#[derive(Resource, Debug, Default)]
+pub struct Exploration {
+    /// Between 0.0 and 1.0.
+    pub fraction: f32,
+    /// Milestones the player has already been rewarded for.
+    milestones: u32,
+}
+
+fn reset_exploration(mut exploration: ResMut<Exploration>) {
+    *exploration = Exploration::default();
+}
+
+fn explore(
+    player: Single<&Transform, With<Player>>,
+    mut chunks: Query<(&mut ExploredCells, &Transform), Without<Player>>,
+    mut exploration: ResMut<Exploration>,
+) {
+    let mut explored = 0;
+    let mut total = 0;
+    for (mut cells, transform) in &mut chunks {
+        let position = player.translation.xy() - transform.translation.xy();
+        for y in 0..SQUARE {
+            for x in 0..SQUARE {
+                // Only mark the component as changed when something new was seen.
+                if !cells.is_explored(x, y) && cell_center(x, y).distance(position) < VISION_RADIUS
+                {
+                    cells.explore(x, y);
+                }
+            }
+        }
+        explored += cells.count();
+        total += SQUARE * SQUARE;
+    }
+    let fraction = explored as f32 / total.max(1) as f32;
+    if exploration.fraction != fraction {
+        exploration.fraction = fraction;
+    }
+}
+
+fn update_fog_textures(
+    chunks: Query<(&ExploredCells, &MeshMaterial2d<TerrainMaterial>), Changed<ExploredCells>>,
+    materials: Res<Assets<TerrainMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (cells, material) in &chunks {
+        if let Some(material) = materials.get(&material.0)
+            && let Some(image) = images.get_mut(&material.fog_texture)
+        {
+            *image = cells.as_tex();
+        }
+    }
+}
+
+fn reward_exploration(
+    mut exploration: ResMut<Exploration>,
+    mut progress: ResMut<PlayerProgress>,
+    mut events: MessageWriter<GameEvent>,
+) {
+    let reached = (exploration.fraction / MILESTONE + 1e-4).floor() as u32;
+    while exploration.milestones < reached {
+        exploration.milestones += 1;
+        let gold = MILESTONE_GOLD * exploration.milestones;
+        progress.gold += gold;
+        progress.xp += MILESTONE_XP;
+        events.write(GameEvent::GoldEarned { amount: gold });
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::math::Vec2;
+
+    #[allow(unused)]
+    use crate::demo::terrain::{
+        fog::{ExploredCells, cell_center},
+        height::{SQUARE, world_2_chunk},
+    };
+
+    #[test]
+    fn cells_are_explored_once() {
+        let mut cells = ExploredCells::new();
+        assert!(cells.explore(3, 5));
+        assert!(!cells.explore(3, 5));
+        assert!(cells.explore(SQUARE - 1, SQUARE - 1));
+        assert!(cells.is_explored(3, 5));
+        assert!(!cells.is_explored(5, 3));
+        assert_eq!(cells.count(), 2);
+
+        assert_eq!(world_2_chunk(cell_center(3, 5)), (3, 5));
+        assert_eq!(cell_center(8, 8), Vec2::splat(128.0));
+    }
+}
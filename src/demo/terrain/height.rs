@@ -44,6 +44,13 @@ pub fn world_2_chunk(p: Vec2) -> (usize, usize) {
     (within_chunk_index.0 as usize, within_chunk_index.1 as usize)
 }
 
+/// The center of the cell at `x` and `y` of a chunk centered on `offset`
+pub fn cell_center(x: usize, y: usize, offset: Vec2) -> Vec2 {
+    let cell_size = (CHUNK_SIZE_PIXELS / SQUARE) as f32;
+    Vec2::new(x as f32, y as f32) * cell_size + offset - cell_size * (SQUARE / 2) as f32
+        + cell_size * 0.5
+}
+
 impl TerrainChunk {
     pub fn zero() -> TerrainChunk {
         let heights = vec![0.0; SQUARE * SQUARE];
@@ -93,15 +100,9 @@ impl TerrainChunk {
             for x in 0..SQUARE {
                 let height = self.get(x, y);
                 if height > WATER_LEVEL {
-                    let x = x as f32 * collider_size + offset.x
-                        - collider_size * (SQUARE / 2) as f32
-                        + collider_size * 0.5;
-                    let y = y as f32 * collider_size + offset.y
-                        - collider_size * (SQUARE / 2) as f32
-                        + collider_size * 0.5;
                     colliders.push((
                         Collider::rectangle(collider_size, collider_size),
-                        Transform::from_xyz(x, y, 0.0),
+                        Transform::from_translation(cell_center(x, y, offset).extend(0.0)),
                     ));
                 }
             }
@@ -111,26 +112,29 @@ impl TerrainChunk {
         colliders
     }
 
-    pub fn spawners(&self, offset: Vec2) -> Vec<Transform> {
-        let mut spawners = Vec::with_capacity(SQUARE * SQUARE);
-        let collider_size = (CHUNK_SIZE_PIXELS / SQUARE) as f32;
-        for y in 0..SQUARE {
-            for x in 0..SQUARE {
-                let height = self.get(x, y);
-                if height < WATER_LEVEL {
-                    let x = x as f32 * collider_size + offset.x
-                        - collider_size * (SQUARE / 2) as f32
-                        + collider_size * 0.5;
-                    let y = y as f32 * collider_size + offset.y
-                        - collider_size * (SQUARE / 2) as f32
-                        + collider_size * 0.5;
-                    spawners.push(Transform::from_xyz(x, y, 0.0));
-                }
-            }
+    /// `p` relative to the center of the chunk, `None` outside of the chunk
+    pub fn height_at(&self, p: Vec2) -> Option<f32> {
+        let half = CHUNK_SIZE_PIXELS as f32 / 2.0;
+        if p.x < -half || p.y < -half || p.x >= half || p.y >= half {
+            return None;
         }
+        let (x, y) = world_2_chunk(p);
+        Some(self.get(x, y))
+    }
 
-        spawners.shrink_to_fit();
-        spawners
+    /// Strips along the border of the chunk, one cell deep
+    pub fn edge_regions(&self, offset: Vec2) -> [Rect; 4] {
+        let half = CHUNK_SIZE_PIXELS as f32 / 2.0;
+        let depth = (CHUNK_SIZE_PIXELS / SQUARE) as f32;
+        let min = offset - half;
+        let max = offset + half;
+
+        [
+            Rect::new(min.x, min.y, max.x, min.y + depth),
+            Rect::new(min.x, max.y - depth, max.x, max.y),
+            Rect::new(min.x, min.y, min.x + depth, max.y),
+            Rect::new(max.x - depth, min.y, max.x, max.y),
+        ]
     }
 }
 
@@ -157,6 +161,10 @@ pub struct TerrainMaterial {
     #[texture(3)]
     #[sampler(4)]
     pub wave_texture: Handle<Image>,
+    /// Explored cells, see [`ExploredCells`](super::fog::ExploredCells).
+    #[texture(5)]
+    #[sampler(6)]
+    pub fog_texture: Handle<Image>,
 }
 
 const FRAGMENT_SHADER_ASSET_PATH: &str = "shaders/terrain.wesl";
@@ -1,5 +1,8 @@
+pub mod fog;
 pub mod height;
+pub mod ports;
 pub mod waves;
+pub mod wind;
 
 use avian2d::prelude::*;
 use bevy::{prelude::*, sprite_render::Material2dPlugin};
@@ -7,9 +10,12 @@ use noiz::prelude::*;
 
 use crate::{
     demo::{
-        enemy::Spawner,
+        run_stats::RunSeed,
+        spawning::SpawnRegion,
         terrain::{
+            fog::ExploredCells,
             height::{CHUNK_SIZE_PIXELS, SQUARE, TerrainChunk, TerrainMaterial, update_time},
+            ports::{coast_cells, pick_port_sites, port},
             waves::Waves,
         },
     },
@@ -18,12 +24,19 @@ use crate::{
 
 pub struct TerrainPlugin;
 
+/// Enemies prefer to sail in from the edges of the map.
+const EDGE_SPAWN_WEIGHT: f32 = 2.0;
+
 /// Modeling the terrain as a height map, adding ports, and active entities ontop
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.add_systems(OnEnter(Screen::Gameplay), spawn_terrain)
             .add_systems(Update, update_time.run_if(in_state(Screen::Gameplay)))
-            .add_plugins(Material2dPlugin::<TerrainMaterial>::default());
+            .add_plugins((
+                Material2dPlugin::<TerrainMaterial>::default(),
+                fog::plugin,
+                wind::plugin,
+            ));
     }
 }
 
@@ -32,49 +45,62 @@ fn spawn_terrain(
     mut materials: ResMut<Assets<TerrainMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut images: ResMut<Assets<Image>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+    seed: Res<RunSeed>,
 ) {
     let mesh = meshes.add(Rectangle::new(
         CHUNK_SIZE_PIXELS as f32,
         CHUNK_SIZE_PIXELS as f32,
     ));
-    let terrain = generate_chunk();
+    let terrain = generate_chunk(seed.0);
 
     let waves = Waves::init(&terrain);
     let wave_texture = images.add(waves.as_tex());
 
+    let explored = ExploredCells::new();
+    let fog_texture = images.add(explored.as_tex());
+
     let height_texture = images.add(terrain.as_tex());
     let material = materials.add(TerrainMaterial {
         time: Vec4::ZERO,
         height_texture,
         wave_texture,
+        fog_texture,
     });
     let land_collider = terrain.land_colliders(Vec2::ZERO);
 
-    for spawner in terrain.spawners(Vec2::ZERO) {
+    for area in terrain.edge_regions(Vec2::ZERO) {
         commands.spawn((
-            Spawner {
-                timer: Timer::from_seconds(2.0, TimerMode::Repeating),
+            SpawnRegion {
+                area,
+                weight: EDGE_SPAWN_WEIGHT,
             },
-            spawner,
+            DespawnOnExit(Screen::Gameplay),
         ));
     }
+    // The player's ship starts out in the middle of the map.
+    for position in pick_port_sites(&coast_cells(&terrain, Vec2::ZERO), Vec2::ZERO) {
+        commands.spawn(port(position, &mut meshes, &mut color_materials));
+    }
     commands.spawn((
         Mesh2d(mesh),
         MeshMaterial2d(material),
         Transform::from_translation(Vec3::ZERO),
         terrain,
+        explored,
         waves,
+        DespawnOnExit(Screen::Gameplay),
     ));
 
     for c in land_collider {
-        commands.spawn((c.0, c.1, RigidBody::Static));
+        commands.spawn((c.0, c.1, RigidBody::Static, DespawnOnExit(Screen::Gameplay)));
     }
 }
 
-fn generate_chunk() -> TerrainChunk {
+fn generate_chunk(seed: u32) -> TerrainChunk {
     let mut t = TerrainChunk::zero();
     let mut noise = Noise::<PerCell<OrthoGrid, Random<SNorm, f32>>>::default();
-    noise.set_seed(123);
+    noise.set_seed(seed);
     noise.set_frequency(1.1);
 
     for y in 0..SQUARE {
@@ -0,0 +1,125 @@
+//! Ports along the coast, where the player's ship is patched up.
+//!
+//! Ports are built on water cells right next to land, spread out over the
+//! world so there is always one within sailing distance. While the player's
+//! ship lies within [`DOCK_RADIUS`] of a port, its hull is repaired much faster
+//! than it is at sea.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::terrain::height::{SQUARE, TerrainChunk, WATER_LEVEL, cell_center},
+    screens::Screen,
+};
+
+/// How many ports the world has at most.
+const PORT_COUNT: usize = 4;
+/// Ports are at least this far apart.
+const MIN_PORT_SPACING: f32 = 1200.0;
+/// Ships this close to a port are docked.
+pub const DOCK_RADIUS: f32 = 250.0;
+
+#[derive(Component, Debug)]
+pub struct Port;
+
+/// Centers of the water cells next to land, in world space.
+pub fn coast_cells(terrain: &TerrainChunk, offset: Vec2) -> Vec<Vec2> {
+    let is_land = |x: usize, y: usize| terrain.get(x, y) > WATER_LEVEL;
+    let mut cells = Vec::new();
+    for y in 0..SQUARE {
+        for x in 0..SQUARE {
+            if is_land(x, y) {
+                continue;
+            }
+            let next_to_land = (x > 0 && is_land(x - 1, y))
+                || (x + 1 < SQUARE && is_land(x + 1, y))
+                || (y > 0 && is_land(x, y - 1))
+                || (y + 1 < SQUARE && is_land(x, y + 1));
+            if next_to_land {
+                cells.push(cell_center(x, y, offset));
+            }
+        }
+    }
+    cells
+}
+
+/// Picks up to [`PORT_COUNT`] sites from `coast`. The first one is the closest
+/// to `start`, every following one is the site furthest from all ports so far.
+pub fn pick_port_sites(coast: &[Vec2], start: Vec2) -> Vec<Vec2> {
+    let Some(first) = coast
+        .iter()
+        .min_by(|a, b| a.distance(start).total_cmp(&b.distance(start)))
+    else {
+        return Vec::new();
+    };
+    let mut sites = vec![*first];
+    while sites.len() < PORT_COUNT {
+        let spacing = |p: &Vec2| {
+            sites
+                .iter()
+                .map(|site| site.distance(*p))
+                .fold(f32::MAX, f32::min)
+        };
+        let Some(next) = coast
+            .iter()
+            .max_by(|a, b| spacing(a).total_cmp(&spacing(b)))
+            .filter(|p| spacing(p) >= MIN_PORT_SPACING)
+        else {
+            break;
+        };
+        sites.push(*next);
+    }
+    sites
+}
+
+pub fn port(
+    position: Vec2,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) -> impl Bundle {
+    (
+        Name::new("Port"),
+        Port,
+        Mesh2d(meshes.add(Annulus::new(DOCK_RADIUS - 12.0, DOCK_RADIUS))),
+        MeshMaterial2d(materials.add(Color::srgba(0.95, 0.85, 0.55, 0.6))),
+        Transform::from_translation(position.extend(0.3)),
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+mod test {
+    #[allow(unused)]
+    use bevy::math::Vec2;
+
+    #[allow(unused)]
+    use crate::demo::terrain::{
+        height::{TerrainChunk, WATER_LEVEL, world_2_chunk},
+        ports::{MIN_PORT_SPACING, PORT_COUNT, coast_cells, pick_port_sites},
+    };
+
+    #[test]
+    fn ports_are_on_the_coast() {
+        // A small island in each corner of the map and one in the middle.
+        let mut terrain = TerrainChunk::zero();
+        for (x, y) in [(1, 1), (1, 14), (14, 1), (14, 14), (8, 8)] {
+            terrain.set(x, y, 1.0);
+        }
+        let coast = coast_cells(&terrain, Vec2::ZERO);
+        assert_eq!(coast.len(), 5 * 4);
+
+        let sites = pick_port_sites(&coast, Vec2::ZERO);
+        assert_eq!(sites.len(), PORT_COUNT);
+        // The first port is at the island in the middle, next to the start.
+        assert!(sites[0].length() < 300.0);
+        for (i, site) in sites.iter().enumerate() {
+            let (x, y) = world_2_chunk(*site);
+            assert!(terrain.get(x, y) <= WATER_LEVEL);
+            for other in &sites[i + 1..] {
+                assert!(site.distance(*other) >= MIN_PORT_SPACING);
+            }
+        }
+
+        let open_sea = coast_cells(&TerrainChunk::zero(), Vec2::ZERO);
+        assert!(pick_port_sites(&open_sea, Vec2::ZERO).is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+//! The wind over the sea.
+//!
+//! Every run the wind blows from a direction picked from the [`RunSeed`], and
+//! over the course of the run it slowly swings back and forth around it.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{hud::RunTime, run_stats::RunSeed},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Wind>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_wind);
+    app.add_systems(
+        Update,
+        drift_wind
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Time for the wind to swing from one side and back.
+const DRIFT_PERIOD_SECS: f32 = 180.0;
+/// How far the wind swings away from its prevailing direction, in radians.
+const DRIFT_SWING: f32 = 0.6;
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Wind {
+    /// The angle of the prevailing direction, counterclockwise from east.
+    prevailing: f32,
+    /// Where the wind is blowing towards right now.
+    pub direction: Vec2,
+}
+
+impl Default for Wind {
+    fn default() -> Self {
+        Wind::new(0)
+    }
+}
+
+impl Wind {
+    pub fn new(seed: u32) -> Wind {
+        let prevailing = StdRng::seed_from_u64(seed.into()).random_range(0.0..TAU);
+        Wind {
+            prevailing,
+            direction: Vec2::from_angle(prevailing),
+        }
+    }
+
+    /// The direction of the wind `secs` into the run.
+    pub fn direction_at(&self, secs: f32) -> Vec2 {
+        let swing = DRIFT_SWING * (secs / DRIFT_PERIOD_SECS * TAU).sin();
+        Vec2::from_angle(self.prevailing + swing)
+    }
+}
+
+fn reset_wind(mut wind: ResMut<Wind>, seed: Res<RunSeed>) {
+    *wind = Wind::new(seed.0);
+}
+
+fn drift_wind(mut wind: ResMut<Wind>, run_time: Res<RunTime>) {
+    wind.direction = wind.direction_at(run_time.0.elapsed_secs());
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::terrain::wind::{DRIFT_PERIOD_SECS, DRIFT_SWING, Wind};
+
+    #[test]
+    fn wind_drifts_around_the_prevailing_direction() {
+        let wind = Wind::new(123);
+        assert_eq!(Wind::new(123).direction, wind.direction);
+        assert_ne!(Wind::new(124).direction, wind.direction);
+
+        for secs in [0.0, 10.0, DRIFT_PERIOD_SECS / 4.0, 500.0] {
+            let direction = wind.direction_at(secs);
+            assert!((direction.length() - 1.0).abs() < 1e-5);
+            assert!(direction.angle_to(wind.direction).abs() <= DRIFT_SWING + 1e-5);
+        }
+        let quarter = wind.direction_at(DRIFT_PERIOD_SECS / 4.0);
+        assert!((quarter.angle_to(wind.direction).abs() - DRIFT_SWING).abs() < 1e-4);
+    }
+}
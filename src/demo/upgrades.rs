@@ -0,0 +1,362 @@
+//! Levelling up and the upgrades offered on a level up.
+//!
+//! Collected XP fills the XP bar. Every time it is full, the game pauses and
+//! the level-up menu offers a few upgrades rolled from the [`UpgradeTable`],
+//! which is loaded from `assets/data/default.upgrades.ron`.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use rand::{Rng, seq::IndexedRandom};
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems, Pause,
+    asset_tracking::LoadResource,
+    demo::{
+        abilities::{Abilities, AbilityKind},
+        loot::PlayerProgress,
+        player::{Player, PlayerStats},
+        stats::{ModifierOp, ModifierSource, Stat, StatModifier, StatModifiers},
+        weapons::{WeaponKind, WeaponSlots},
+    },
+    menus::Menu,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<UpgradeTable>();
+    app.init_asset_loader::<UpgradeTableLoader>();
+    app.load_resource::<UpgradeAssets>();
+    app.init_resource::<PickedUpgrades>();
+    app.init_resource::<UpgradeOffer>();
+    app.add_message::<UpgradeChosen>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_upgrades);
+    app.add_systems(
+        Update,
+        check_level_up
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(in_state(Menu::None))),
+    );
+    // Runs while the game is paused for the level up.
+    app.add_systems(
+        Update,
+        apply_chosen_upgrade
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Menu::LevelUp)),
+    );
+}
+
+/// Upgrades offered per level up.
+pub const OFFERED_UPGRADES: usize = 3;
+
+/// XP needed to get from `level` to the next one.
+pub fn xp_to_next_level(level: u32) -> u32 {
+    20 + 15 * level
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum Rarity {
+    Common,
+    Rare,
+    Epic,
+}
+
+impl Rarity {
+    pub fn color(self) -> Color {
+        match self {
+            Rarity::Common => Color::srgb(0.6, 0.6, 0.6),
+            Rarity::Rare => Color::srgb(0.3, 0.55, 0.95),
+            Rarity::Epic => Color::srgb(0.7, 0.35, 0.9),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum UpgradeEffect {
+    /// Adds a modifier to a stat of the player.
+    Stat { stat: Stat, op: ModifierOp },
+    /// Mounts a new weapon in a free slot.
+    NewWeapon(WeaponKind),
+    /// Increases the damage of all mounted weapons of a kind by a share.
+    WeaponDamage { weapon: WeaponKind, amount: f32 },
+    /// Puts an active ability into a free slot.
+    Ability(AbilityKind),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Upgrade {
+    pub name: String,
+    pub description: String,
+    pub rarity: Rarity,
+    pub effect: UpgradeEffect,
+    /// How often the upgrade can be picked during a run, unlimited if unset.
+    #[serde(default)]
+    pub max_picks: Option<u32>,
+}
+
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct UpgradeTable {
+    pub rarity_weights: HashMap<Rarity, f32>,
+    pub upgrades: Vec<Upgrade>,
+}
+
+impl UpgradeTable {
+    /// Rolls up to `count` different upgrades, as indices into `upgrades`.
+    /// Upgrades that were picked too often or are not `available` are skipped.
+    pub fn roll(
+        &self,
+        count: usize,
+        picked: &PickedUpgrades,
+        available: impl Fn(&Upgrade) -> bool,
+        rng: &mut impl Rng,
+    ) -> Vec<usize> {
+        let mut candidates: Vec<usize> = (0..self.upgrades.len())
+            .filter(|i| {
+                let upgrade = &self.upgrades[*i];
+                let picks = picked.0.get(&upgrade.name).copied().unwrap_or(0);
+                upgrade.max_picks.is_none_or(|max| picks < max) && available(upgrade)
+            })
+            .collect();
+
+        let mut offer = Vec::new();
+        while offer.len() < count {
+            let Ok(choice) = candidates.choose_weighted(rng, |i| {
+                self.rarity_weights
+                    .get(&self.upgrades[*i].rarity)
+                    .copied()
+                    .unwrap_or(0.0)
+            }) else {
+                break;
+            };
+            let choice = *choice;
+            candidates.retain(|i| *i != choice);
+            offer.push(choice);
+        }
+        offer
+    }
+}
+
+#[derive(Default, TypePath)]
+struct UpgradeTableLoader;
+
+impl AssetLoader for UpgradeTableLoader {
+    type Asset = UpgradeTable;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<UpgradeTable, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["upgrades.ron"]
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct UpgradeAssets {
+    #[dependency]
+    pub table: Handle<UpgradeTable>,
+}
+
+impl FromWorld for UpgradeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            table: assets.load("data/default.upgrades.ron"),
+        }
+    }
+}
+
+/// How often each upgrade was picked during the current run, by name.
+#[derive(Resource, Debug, Default)]
+pub struct PickedUpgrades(pub HashMap<String, u32>);
+
+/// The upgrades offered by the open level-up menu, as indices into the [`UpgradeTable`].
+#[derive(Resource, Debug, Default)]
+pub struct UpgradeOffer(pub Vec<usize>);
+
+/// The player picked an upgrade from the [`UpgradeOffer`].
+#[derive(Message, Debug, Clone, Copy)]
+pub struct UpgradeChosen(pub usize);
+
+fn reset_upgrades(mut picked: ResMut<PickedUpgrades>) {
+    picked.0.clear();
+}
+
+fn check_level_up(
+    mut progress: ResMut<PlayerProgress>,
+    mut offer: ResMut<UpgradeOffer>,
+    picked: Res<PickedUpgrades>,
+    upgrade_assets: Res<UpgradeAssets>,
+    tables: Res<Assets<UpgradeTable>>,
+    player: Single<(&WeaponSlots, &Abilities), With<Player>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    let needed = xp_to_next_level(progress.level);
+    if progress.xp < needed {
+        return;
+    }
+    progress.xp -= needed;
+    progress.level += 1;
+
+    let Some(table) = tables.get(&upgrade_assets.table) else {
+        return;
+    };
+    let (slots, abilities) = *player;
+    offer.0 = table.roll(
+        OFFERED_UPGRADES,
+        &picked,
+        |upgrade| match upgrade.effect {
+            UpgradeEffect::Stat { .. } => true,
+            UpgradeEffect::NewWeapon(kind) => slots.can_mount(kind),
+            UpgradeEffect::WeaponDamage { weapon, .. } => slots.has(weapon),
+            UpgradeEffect::Ability(kind) => abilities.can_grant(kind),
+        },
+        &mut rand::rng(),
+    );
+    if !offer.0.is_empty() {
+        next_pause.set(Pause(true));
+        next_menu.set(Menu::LevelUp);
+    }
+}
+
+fn apply_chosen_upgrade(
+    mut chosen: MessageReader<UpgradeChosen>,
+    offer: Res<UpgradeOffer>,
+    mut picked: ResMut<PickedUpgrades>,
+    upgrade_assets: Res<UpgradeAssets>,
+    tables: Res<Assets<UpgradeTable>>,
+    player: Single<
+        (
+            &PlayerStats,
+            &mut StatModifiers,
+            &mut WeaponSlots,
+            &mut Abilities,
+        ),
+        With<Player>,
+    >,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    let Some(UpgradeChosen(choice)) = chosen.read().last().copied() else {
+        return;
+    };
+    let Some(table) = tables.get(&upgrade_assets.table) else {
+        return;
+    };
+    let Some(upgrade) = offer.0.get(choice).and_then(|i| table.upgrades.get(*i)) else {
+        return;
+    };
+
+    let (stats, mut modifiers, mut slots, mut abilities) = player.into_inner();
+    match upgrade.effect {
+        UpgradeEffect::Stat { stat, op } => {
+            modifiers.add(StatModifier {
+                stat,
+                op,
+                source: ModifierSource::Upgrade(upgrade.name.clone()),
+            });
+        }
+        UpgradeEffect::NewWeapon(kind) => {
+            slots.mount(kind, stats);
+        }
+        UpgradeEffect::WeaponDamage { weapon, amount } => {
+            for mounted in slots.weapons_mut().filter(|w| w.kind() == weapon) {
+                mounted.scale_damage(1.0 + amount);
+            }
+        }
+        UpgradeEffect::Ability(kind) => {
+            abilities.grant(kind);
+        }
+    }
+    *picked.0.entry(upgrade.name.clone()).or_default() += 1;
+    next_menu.set(Menu::None);
+}
+
+mod test {
+    #[allow(unused)]
+    use std::collections::HashMap;
+
+    #[allow(unused)]
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[allow(unused)]
+    use crate::demo::{
+        stats::{ModifierOp, Stat},
+        upgrades::{PickedUpgrades, Rarity, Upgrade, UpgradeEffect, UpgradeTable},
+        weapons::WeaponKind,
+    };
+
+    #[allow(unused)]
+    fn upgrade(
+        name: &str,
+        rarity: Rarity,
+        effect: UpgradeEffect,
+        max_picks: Option<u32>,
+    ) -> Upgrade {
+        Upgrade {
+            name: name.into(),
+            description: String::new(),
+            rarity,
+            effect,
+            max_picks,
+        }
+    }
+
+    #[test]
+    fn roll_respects_rules() {
+        let stat = UpgradeEffect::Stat {
+            stat: Stat::ProjectileDamage,
+            op: ModifierOp::Add(0.1),
+        };
+        let table = UpgradeTable {
+            rarity_weights: HashMap::from_iter([(Rarity::Common, 1.0), (Rarity::Rare, 1.0)]),
+            upgrades: vec![
+                upgrade("a", Rarity::Common, stat.clone(), None),
+                upgrade("b", Rarity::Common, stat.clone(), Some(1)),
+                upgrade(
+                    "c",
+                    Rarity::Rare,
+                    UpgradeEffect::NewWeapon(WeaponKind::Cannon),
+                    None,
+                ),
+                upgrade("d", Rarity::Epic, stat.clone(), None),
+            ],
+        };
+        let mut picked = PickedUpgrades::default();
+        picked.0.insert("b".into(), 1);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..50 {
+            let offer = table.roll(3, &picked, |_| true, &mut rng);
+            // "b" is used up and "d" has no weight.
+            assert_eq!(offer.len(), 2);
+            assert!(offer.contains(&0) && offer.contains(&2));
+
+            let offer = table.roll(3, &picked, |u| u.name != "c", &mut rng);
+            assert_eq!(offer, vec![0]);
+        }
+    }
+
+    #[test]
+    fn upgrade_table_parses() {
+        let table: UpgradeTable =
+            ron::de::from_str(include_str!("../../assets/data/default.upgrades.ron")).unwrap();
+        assert!(!table.upgrades.is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Enemies attack in waves. Every few waves is a milestone wave that is led by
+//! a boss.
+//!
+//! The [`WaveDirector`] only decides what to spawn, the `spawning` module
+//! decides where.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        enemy::{Enemy, EnemyKind},
+        events::GameEvent,
+        spawning::SpawnMarker,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<WaveDirector>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_waves);
+    app.add_systems(
+        Update,
+        advance_waves
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Every n-th wave is a boss wave.
+pub const BOSS_WAVE_INTERVAL: u32 = 5;
+/// First wave with fire ships.
+const FIRE_SHIP_WAVE: u32 = 3;
+const INTERMISSION_SECS: f32 = 5.0;
+
+#[derive(Resource)]
+pub struct WaveDirector {
+    /// The current wave, starting at 1. 0 before the first wave.
+    pub wave: u32,
+    /// Enemies of the current wave that still have to be spawned, taken from the back.
+    pub pending: Vec<EnemyKind>,
+    /// Pause between a cleared wave and the next one.
+    pub intermission: Timer,
+}
+
+impl Default for WaveDirector {
+    fn default() -> Self {
+        Self {
+            wave: 0,
+            pending: Vec::new(),
+            intermission: Timer::from_seconds(INTERMISSION_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl WaveDirector {
+    pub fn is_milestone(wave: u32) -> bool {
+        wave > 0 && wave.is_multiple_of(BOSS_WAVE_INTERVAL)
+    }
+
+    /// The enemies making up a wave, in spawn order.
+    pub fn composition(wave: u32) -> Vec<EnemyKind> {
+        let raiders = (5 + wave * 3).min(60) as usize;
+        let mut enemies = vec![EnemyKind::Raider; raiders];
+        // From the third wave on, every fourth ship is a fire ship.
+        if wave >= FIRE_SHIP_WAVE {
+            for kind in enemies.iter_mut().skip(3).step_by(4) {
+                *kind = EnemyKind::FireShip;
+            }
+        }
+        if Self::is_milestone(wave) {
+            // The boss shows up first, its escort trickles in afterwards.
+            enemies.insert(0, EnemyKind::Boss);
+            enemies.truncate(raiders / 2 + 1);
+        }
+        enemies
+    }
+
+    fn start_next_wave(&mut self) {
+        self.wave += 1;
+        self.pending = Self::composition(self.wave);
+        // Spawning pops from the back.
+        self.pending.reverse();
+        self.intermission.reset();
+    }
+}
+
+fn reset_waves(mut director: ResMut<WaveDirector>) {
+    *director = WaveDirector::default();
+}
+
+fn advance_waves(
+    time: Res<Time>,
+    mut director: ResMut<WaveDirector>,
+    enemies: Query<(), With<Enemy>>,
+    markers: Query<(), With<SpawnMarker>>,
+    mut events: MessageWriter<GameEvent>,
+) {
+    if !director.pending.is_empty() || !enemies.is_empty() || !markers.is_empty() {
+        return;
+    }
+    // The intermission starts right after the last enemy of a wave sank.
+    if director.wave > 0 && director.intermission.elapsed().is_zero() {
+        events.write(GameEvent::WaveCleared {
+            wave: director.wave,
+        });
+    }
+    director.intermission.tick(time.delta());
+    if director.intermission.is_finished() {
+        director.start_next_wave();
+    }
+}
+
+mod test {
+    #[allow(unused)]
+    use crate::demo::{enemy::EnemyKind, wave_director::WaveDirector};
+
+    #[test]
+    fn milestone_waves_have_a_boss() {
+        for wave in 1..=20 {
+            let bosses = WaveDirector::composition(wave)
+                .into_iter()
+                .filter(|kind| *kind == EnemyKind::Boss)
+                .count();
+            let expected = if wave % 5 == 0 { 1 } else { 0 };
+            assert_eq!(bosses, expected, "wave {wave}");
+        }
+        assert_eq!(WaveDirector::composition(5)[0], EnemyKind::Boss);
+    }
+}
@@ -1,36 +1,143 @@
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+
+use avian2d::prelude::*;
 use bevy::{
     prelude::*,
     render::render_resource::AsBindGroup,
     sprite_render::{Material2d, Material2dPlugin},
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    demo::{Health, enemy::Enemy, forward_vec, player::PlayerStats},
+    PausableSystems,
+    demo::{
+        GameCollisionLayer, Health,
+        damage::{DamageEvent, DamageKind},
+        enemy::Emerging,
+        events::GameEvent,
+        forward_vec,
+        hull::{HullSection, HullSections},
+        player::PlayerStats,
+        status_effects::{
+            ApplyStatus, InflictsStatus, StatusEffect, StatusEffectKind, StatusEffects,
+        },
+    },
     screens::Screen,
 };
 
 pub struct WeaponPlugin;
 
+/// The kinds of [`WeaponType`], without their state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub enum WeaponKind {
+    Cannon,
+    ChainShot,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 2] = [WeaponKind::Cannon, WeaponKind::ChainShot];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WeaponKind::Cannon => "Cannon",
+            WeaponKind::ChainShot => "Chain Shot",
+        }
+    }
+}
+
 #[derive(Clone)]
-#[allow(dead_code)]
 pub enum WeaponType {
-    Canon { cooldown: Timer, damage: f32 },
+    Canon {
+        cooldown: Timer,
+        damage: f32,
+    },
+    /// Two balls chained together, tearing the rigging of the ship it hits.
+    ChainShot {
+        cooldown: Timer,
+        damage: f32,
+        slow: f32,
+    },
+}
+
+impl WeaponKind {
+    /// Reload time before the [`PlayerStats::projectile_rate_percentage`] is applied.
+    pub fn base_cooldown_secs(self) -> f32 {
+        match self {
+            WeaponKind::Cannon => 3.0,
+            WeaponKind::ChainShot => 4.0,
+        }
+    }
 }
 
 impl WeaponType {
     pub fn default_cannon(player: &PlayerStats) -> WeaponType {
         WeaponType::Canon {
             cooldown: Timer::from_seconds(
-                3.0 * player.projectile_rate_percentage,
+                WeaponKind::Cannon.base_cooldown_secs() * player.projectile_rate_percentage,
                 TimerMode::Repeating,
             ),
             damage: 30.0,
         }
     }
 
+    pub fn default_chain_shot(player: &PlayerStats) -> WeaponType {
+        WeaponType::ChainShot {
+            cooldown: Timer::from_seconds(
+                WeaponKind::ChainShot.base_cooldown_secs() * player.projectile_rate_percentage,
+                TimerMode::Repeating,
+            ),
+            damage: 15.0,
+            slow: 0.5,
+        }
+    }
+
+    pub fn new(kind: WeaponKind, player: &PlayerStats) -> WeaponType {
+        match kind {
+            WeaponKind::Cannon => WeaponType::default_cannon(player),
+            WeaponKind::ChainShot => WeaponType::default_chain_shot(player),
+        }
+    }
+
+    pub fn kind(&self) -> WeaponKind {
+        match self {
+            WeaponType::Canon { .. } => WeaponKind::Cannon,
+            WeaponType::ChainShot { .. } => WeaponKind::ChainShot,
+        }
+    }
+
+    pub fn scale_damage(&mut self, factor: f32) {
+        match self {
+            WeaponType::Canon { damage, .. } | WeaponType::ChainShot { damage, .. } => {
+                *damage *= factor;
+            }
+        }
+    }
+
+    /// Rescales the reload time to a new [`PlayerStats::projectile_rate_percentage`],
+    /// keeping the progress of the current reload.
+    pub fn set_rate(&mut self, rate: f32) {
+        let duration = Duration::from_secs_f32(self.kind().base_cooldown_secs() * rate);
+        match self {
+            WeaponType::Canon { cooldown, .. } | WeaponType::ChainShot { cooldown, .. } => {
+                let fraction = cooldown.fraction();
+                cooldown.set_duration(duration);
+                cooldown.set_elapsed(duration.mul_f32(fraction));
+            }
+        }
+    }
+
+    /// How far the current reload has progressed, 1.0 when loaded.
+    pub fn reload_fraction(&self) -> f32 {
+        match self {
+            WeaponType::Canon { cooldown, .. } | WeaponType::ChainShot { cooldown, .. } => {
+                cooldown.fraction()
+            }
+        }
+    }
+
     fn should_fire(&mut self, time: &Time) -> bool {
         match self {
-            WeaponType::Canon { cooldown, .. } => {
+            WeaponType::Canon { cooldown, .. } | WeaponType::ChainShot { cooldown, .. } => {
                 cooldown.tick(time.delta());
                 cooldown.is_finished()
             }
@@ -38,24 +145,52 @@ impl WeaponType {
     }
     fn fire(
         &self,
+        commands: &mut Commands,
+        events: &mut MessageWriter<GameEvent>,
+        source: Entity,
         player: &PlayerStats,
         transform: Transform,
         meshes: &mut Assets<Mesh>,
         materials: &mut Assets<WeaponMaterial>,
-    ) -> impl Bundle {
-        let mesh = meshes.add(Rectangle::new(30.0, 30.0));
-        let material = materials.add(WeaponMaterial { time: Vec4::ZERO });
+    ) {
+        let speed = 500.0 * player.projectile_speed_percentage;
+        events.write(GameEvent::ShotFired {
+            weapon: self.kind(),
+        });
         match self {
-            WeaponType::Canon { damage, .. } => (
-                CanonBall {
-                    remaining: Timer::from_seconds(3.0, TimerMode::Once),
-                    speed: 500.0 * player.projectile_speed_percentage,
-                    damage: *damage * player.projectile_damage_percentage,
-                },
-                transform,
-                Mesh2d(mesh),
-                MeshMaterial2d(material),
-            ),
+            WeaponType::Canon { damage, .. } => {
+                commands.spawn((
+                    cannon_ball(
+                        transform,
+                        speed,
+                        *damage * player.projectile_damage_percentage,
+                        GameCollisionLayer::Enemy,
+                        source,
+                        meshes,
+                        materials,
+                    ),
+                    FiredFrom(WeaponKind::Cannon),
+                ));
+            }
+            WeaponType::ChainShot { damage, slow, .. } => {
+                commands
+                    .spawn(cannon_ball(
+                        transform,
+                        speed,
+                        *damage * player.projectile_damage_percentage,
+                        GameCollisionLayer::Enemy,
+                        source,
+                        meshes,
+                        materials,
+                    ))
+                    .insert(FiredFrom(WeaponKind::ChainShot))
+                    .insert(InflictsStatus(StatusEffect::new(
+                        StatusEffectKind::Slowed,
+                        Duration::from_secs_f32(CHAIN_SHOT_SLOW_SECS),
+                        *slow,
+                        Some(source),
+                    )));
+            }
         }
     }
 }
@@ -65,6 +200,44 @@ pub struct CanonBall {
     pub remaining: Timer,
     pub speed: f32,
     pub damage: f32,
+    /// The layer of ships this ball can hit.
+    pub targets: GameCollisionLayer,
+    /// The ship that fired the ball.
+    pub source: Entity,
+}
+
+/// The weapon of the player that fired a cannon ball.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FiredFrom(pub WeaponKind);
+
+const CANNON_BALL_RADIUS: f32 = 15.0;
+const CHAIN_SHOT_SLOW_SECS: f32 = 3.0;
+
+/// A cannon ball flying along the forward direction of `transform`.
+pub fn cannon_ball(
+    transform: Transform,
+    speed: f32,
+    damage: f32,
+    targets: GameCollisionLayer,
+    source: Entity,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<WeaponMaterial>,
+) -> impl Bundle {
+    let mesh = meshes.add(Rectangle::from_length(CANNON_BALL_RADIUS * 2.0));
+    let material = materials.add(WeaponMaterial { time: Vec4::ZERO });
+    (
+        CanonBall {
+            remaining: Timer::from_seconds(3.0, TimerMode::Once),
+            speed,
+            damage,
+            targets,
+            source,
+        },
+        transform,
+        Mesh2d(mesh),
+        MeshMaterial2d(material),
+        DespawnOnExit(Screen::Gameplay),
+    )
 }
 
 #[derive(Component)]
@@ -72,6 +245,89 @@ pub struct WeaponSlots {
     pub left: [Option<WeaponType>; 3],
     pub right: [Option<WeaponType>; 3],
     pub front: Option<WeaponType>,
+    /// How many of the `left` and `right` slots the hull has room for.
+    pub broadside_mounts: usize,
+    pub bow_mount: bool,
+}
+
+impl WeaponSlots {
+    /// Empty slots for a hull with the given mounts.
+    pub fn new(broadside_mounts: usize, bow_mount: bool) -> WeaponSlots {
+        WeaponSlots {
+            left: [None, None, None],
+            right: [None, None, None],
+            front: None,
+            broadside_mounts,
+            bow_mount,
+        }
+    }
+
+    pub fn weapons_mut(&mut self) -> impl Iterator<Item = &mut WeaponType> {
+        self.left
+            .iter_mut()
+            .chain(self.right.iter_mut())
+            .chain(std::iter::once(&mut self.front))
+            .flatten()
+    }
+
+    pub fn has(&self, kind: WeaponKind) -> bool {
+        self.left
+            .iter()
+            .chain(self.right.iter())
+            .chain(std::iter::once(&self.front))
+            .flatten()
+            .any(|weapon| weapon.kind() == kind)
+    }
+
+    /// Index of the first side where both the left and the right slot are free.
+    fn free_broadside(&self) -> Option<usize> {
+        (0..self.broadside_mounts.min(self.left.len()))
+            .find(|i| self.left[*i].is_none() && self.right[*i].is_none())
+    }
+
+    /// Whether there is room for another weapon of this kind.
+    pub fn can_mount(&self, kind: WeaponKind) -> bool {
+        match kind {
+            WeaponKind::Cannon => self.free_broadside().is_some(),
+            WeaponKind::ChainShot => {
+                self.bow_mount
+                    && self
+                        .front
+                        .as_ref()
+                        .is_none_or(|front| front.kind() != WeaponKind::ChainShot)
+            }
+        }
+    }
+
+    /// Puts a weapon on the bow, replacing the one that was there.
+    pub fn mount_bow(&mut self, kind: WeaponKind, player: &PlayerStats) -> bool {
+        if !self.bow_mount {
+            return false;
+        }
+        self.front = Some(WeaponType::new(kind, player));
+        true
+    }
+
+    /// Cannons are mounted in pairs, one on each side. Chain shots replace the
+    /// bow gun.
+    pub fn mount(&mut self, kind: WeaponKind, player: &PlayerStats) -> bool {
+        match kind {
+            WeaponKind::Cannon => {
+                let Some(i) = self.free_broadside() else {
+                    return false;
+                };
+                self.left[i] = Some(WeaponType::new(kind, player));
+                self.right[i] = Some(WeaponType::new(kind, player));
+            }
+            WeaponKind::ChainShot => {
+                if !self.can_mount(kind) {
+                    return false;
+                }
+                self.mount_bow(kind, player);
+            }
+        }
+        true
+    }
 }
 
 impl Plugin for WeaponPlugin {
@@ -85,6 +341,7 @@ impl Plugin for WeaponPlugin {
                 cannonball_despawn,
                 cannon_ball_hit,
             )
+                .in_set(PausableSystems)
                 .run_if(in_state(Screen::Gameplay)),
         )
         .add_plugins(Material2dPlugin::<WeaponMaterial>::default());
@@ -93,39 +350,67 @@ impl Plugin for WeaponPlugin {
 
 fn eval_weapons(
     time: Res<Time>,
-    mut weapon_holders: Query<(&mut WeaponSlots, &Transform, &PlayerStats)>,
+    mut weapon_holders: Query<(
+        Entity,
+        &mut WeaponSlots,
+        &Transform,
+        &PlayerStats,
+        Option<&StatusEffects>,
+        Option<&HullSections>,
+    )>,
     mut commands: Commands,
+    mut events: MessageWriter<GameEvent>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<WeaponMaterial>>,
 ) {
-    for (mut weapon_holder, transform, player) in &mut weapon_holders {
+    for (holder, mut weapon_holder, transform, player, status, sections) in &mut weapon_holders {
+        // Stunned crews don't man the guns.
+        if status.is_some_and(|status| status.get(StatusEffectKind::Stunned).is_some()) {
+            continue;
+        }
+        // The `left` slots sit on the starboard side, see `left_weapon_transform`.
+        let destroyed = |section| sections.is_some_and(|s| s.is_destroyed(section));
+        let left_disabled = destroyed(HullSection::Starboard);
+        let right_disabled = destroyed(HullSection::Port);
         let angle = transform.rotation.to_euler(EulerRot::XYZ).2 + std::f32::consts::FRAC_PI_2;
         let forward = Vec2::new(angle.cos(), angle.sin());
 
         for (i, left_slot) in weapon_holder.left.iter_mut().enumerate() {
+            if left_disabled {
+                continue;
+            }
             if let Some(left_slot) = left_slot {
                 if left_slot.should_fire(&time) {
                     let weapon_transform = left_weapon_transform(transform, forward, angle, i);
 
-                    commands.spawn(left_slot.fire(
+                    left_slot.fire(
+                        &mut commands,
+                        &mut events,
+                        holder,
                         player,
                         weapon_transform,
                         &mut meshes,
                         &mut materials,
-                    ));
+                    );
                 }
             }
         }
         for (i, right_slot) in weapon_holder.right.iter_mut().enumerate() {
+            if right_disabled {
+                continue;
+            }
             if let Some(right_slot) = right_slot {
                 if right_slot.should_fire(&time) {
                     let weapon_transform = right_weapon_transform(transform, forward, angle, i);
-                    commands.spawn(right_slot.fire(
+                    right_slot.fire(
+                        &mut commands,
+                        &mut events,
+                        holder,
                         player,
                         weapon_transform,
                         &mut meshes,
                         &mut materials,
-                    ));
+                    );
                 }
             }
         }
@@ -142,7 +427,15 @@ fn eval_weapons(
                     Vec3::Z,
                     angle - std::f32::consts::FRAC_PI_2,
                 ));
-                commands.spawn(front.fire(player, weapon_transform, &mut meshes, &mut materials));
+                front.fire(
+                    &mut commands,
+                    &mut events,
+                    holder,
+                    player,
+                    weapon_transform,
+                    &mut meshes,
+                    &mut materials,
+                );
             }
         }
     }
@@ -187,10 +480,45 @@ fn right_weapon_transform(
         .with_rotation(Quat::from_axis_angle(Vec3::Z, forward_angle))
 }
 
-fn cannonball_flight(mut balls: Query<(&mut Transform, &CanonBall)>, time: Res<Time>) {
-    for (mut ball, stats) in &mut balls {
-        let forward = forward_vec(*ball) * stats.speed * time.delta_secs();
-        ball.translation += Vec3::new(forward.x, forward.y, 0.0);
+/// Cannon balls with this component bounce off islands instead of sinking.
+#[derive(Component)]
+pub struct Ricochet {
+    pub bounces: u32,
+}
+
+fn cannonball_flight(
+    mut balls: Query<(Entity, &mut Transform, &CanonBall, Option<&mut Ricochet>)>,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut commands: Commands,
+) {
+    let terrain = SpatialQueryFilter::from_mask(GameCollisionLayer::Terrain);
+    for (entity, mut ball, stats, ricochet) in &mut balls {
+        let distance = stats.speed * time.delta_secs();
+        let Ok(direction) = Dir2::new(forward_vec(*ball)) else {
+            continue;
+        };
+        let Some(hit) =
+            spatial_query.cast_ray(ball.translation.xy(), direction, distance, true, &terrain)
+        else {
+            let forward = direction * distance;
+            ball.translation += Vec3::new(forward.x, forward.y, 0.0);
+            continue;
+        };
+
+        match ricochet {
+            Some(mut ricochet) if ricochet.bounces > 0 => {
+                ricochet.bounces -= 1;
+                let reflected = direction.reflect(hit.normal);
+                let impact = ball.translation.xy() + direction * hit.distance;
+                ball.translation =
+                    (impact + reflected * (distance - hit.distance)).extend(ball.translation.z);
+                ball.rotation = Quat::from_rotation_z(reflected.to_angle() - FRAC_PI_2);
+            }
+            _ => {
+                commands.entity(entity).despawn();
+            }
+        }
     }
 }
 fn cannonball_despawn(
@@ -207,24 +535,55 @@ fn cannonball_despawn(
 }
 
 fn cannon_ball_hit(
-    balls: Query<(Entity, &Transform, &CanonBall), Without<Enemy>>,
-    mut enemies: Query<(Entity, &Transform, &mut Health), (With<Enemy>, Without<CanonBall>)>,
+    balls: Query<(
+        Entity,
+        &Transform,
+        &CanonBall,
+        Option<&InflictsStatus>,
+        Option<&FiredFrom>,
+    )>,
+    ships: Query<(), (With<Health>, Without<Emerging>)>,
+    colliders: Query<&ColliderOf>,
+    spatial_query: SpatialQuery,
     mut commands: Commands,
+    mut damage: MessageWriter<DamageEvent>,
+    mut status: MessageWriter<ApplyStatus>,
+    mut events: MessageWriter<GameEvent>,
 ) {
-    for ball in balls {
-        for (enemy, enemy_transform, mut enemy_health) in &mut enemies {
-            if ball
-                .1
-                .translation
-                .distance_squared(enemy_transform.translation)
-                < 1000.0
-            {
-                commands.entity(ball.0).despawn();
-                enemy_health.0 -= ball.2.damage as i32;
-                if enemy_health.0 <= 0 {
-                    commands.entity(enemy).despawn();
-                }
-            }
+    let ball_shape = Collider::circle(CANNON_BALL_RADIUS);
+    for (ball, transform, stats, inflicts, fired_from) in &balls {
+        let hit = spatial_query
+            .shape_intersections(
+                &ball_shape,
+                transform.translation.xy(),
+                0.0,
+                &SpatialQueryFilter::from_mask(stats.targets),
+            )
+            .into_iter()
+            .filter_map(|collider| colliders.get(collider).ok())
+            .map(|collider| collider.body)
+            .find(|body| ships.contains(*body));
+        let Some(ship) = hit else {
+            continue;
+        };
+
+        commands.entity(ball).despawn();
+        damage.write(DamageEvent {
+            target: ship,
+            amount: stats.damage,
+            kind: DamageKind::Cannon,
+            source: Some(stats.source),
+            position: Some(transform.translation.xy()),
+            weapon: fired_from.map(|fired_from| fired_from.0),
+        });
+        if let Some(FiredFrom(weapon)) = fired_from {
+            events.write(GameEvent::WeaponHit { weapon: *weapon });
+        }
+        if let Some(InflictsStatus(effect)) = inflicts {
+            status.write(ApplyStatus {
+                target: ship,
+                effect: effect.clone(),
+            });
         }
     }
 }
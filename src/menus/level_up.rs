@@ -0,0 +1,54 @@
+//! The level-up menu, offering a choice of upgrades.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::upgrades::{UpgradeAssets, UpgradeChosen, UpgradeOffer, UpgradeTable},
+    menus::Menu,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::LevelUp), spawn_level_up_menu);
+}
+
+fn spawn_level_up_menu(
+    mut commands: Commands,
+    offer: Res<UpgradeOffer>,
+    upgrade_assets: Res<UpgradeAssets>,
+    tables: Res<Assets<UpgradeTable>>,
+) {
+    let Some(table) = tables.get(&upgrade_assets.table) else {
+        return;
+    };
+    commands
+        .spawn((
+            widget::ui_root("Level Up Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::LevelUp),
+            children![widget::header("Level up!")],
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Name::new("Upgrade Cards"),
+                    Node {
+                        column_gap: px(24),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    for (choice, i) in offer.0.iter().enumerate() {
+                        let upgrade = &table.upgrades[*i];
+                        parent.spawn(widget::card(
+                            upgrade.name.clone(),
+                            upgrade.description.clone(),
+                            upgrade.rarity.color(),
+                            move |_: On<Pointer<Click>>, mut chosen: MessageWriter<UpgradeChosen>| {
+                                chosen.write(UpgradeChosen(choice));
+                            },
+                        ));
+                    }
+                });
+        });
+}
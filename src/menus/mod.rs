@@ -1,9 +1,12 @@
 //! The game's menus and transitions between them.
 
 mod credits;
+mod level_up;
 mod main;
 mod pause;
+mod records;
 mod settings;
+mod ship_select;
 
 use bevy::prelude::*;
 
@@ -12,9 +15,12 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_plugins((
         credits::plugin,
+        level_up::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
+        records::plugin,
+        ship_select::plugin,
     ));
 }
 
@@ -26,4 +32,7 @@ pub enum Menu {
     Credits,
     Settings,
     Pause,
+    LevelUp,
+    ShipSelect,
+    Records,
 }
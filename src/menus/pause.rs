@@ -2,7 +2,7 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{demo::relics::Relics, menus::Menu, screens::Screen, theme::widget};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
@@ -12,18 +12,44 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-fn spawn_pause_menu(mut commands: Commands) {
-    commands.spawn((
-        widget::ui_root("Pause Menu"),
-        GlobalZIndex(2),
-        DespawnOnExit(Menu::Pause),
-        children![
-            widget::header("Game paused"),
-            widget::button("Continue", close_menu),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Quit to title", quit_to_title),
-        ],
-    ));
+fn spawn_pause_menu(mut commands: Commands, relics: Query<&Relics>) {
+    commands
+        .spawn((
+            widget::ui_root("Pause Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Pause),
+            children![
+                widget::header("Game paused"),
+                widget::button("Continue", close_menu),
+                widget::button("Settings", open_settings_menu),
+                widget::button("Quit to title", quit_to_title),
+            ],
+        ))
+        .with_children(|parent| {
+            let Some(relics) = relics.iter().find(|relics| !relics.0.is_empty()) else {
+                return;
+            };
+            parent
+                .spawn((
+                    Name::new("Relics"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: px(6),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(widget::header("Relics"));
+                    for relic in &relics.0 {
+                        parent.spawn(widget::label(format!(
+                            "{}: {}",
+                            relic.name(),
+                            relic.description()
+                        )));
+                    }
+                });
+        });
 }
 
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
@@ -0,0 +1,87 @@
+//! The records menu, listing the best runs.
+
+use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{demo::high_scores::HighScores, menus::Menu, theme::prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Records), spawn_records_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Records).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+/// Only the best runs fit on the screen.
+const SHOWN_RECORDS: usize = 10;
+const COLUMNS: [&str; 7] = ["#", "Mode", "Ship", "Seed", "Waves", "Kills", "Score"];
+
+fn spawn_records_menu(mut commands: Commands, high_scores: Res<HighScores>) {
+    let table = if high_scores.records.is_empty() {
+        commands.spawn(widget::label("No records yet")).id()
+    } else {
+        commands.spawn(records_table(&high_scores)).id()
+    };
+    commands
+        .spawn((
+            widget::ui_root("Records Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Records),
+            children![widget::header("Records")],
+        ))
+        .add_child(table)
+        .with_child(widget::button("Back", go_back_on_click));
+}
+
+fn records_table(high_scores: &HighScores) -> impl Bundle {
+    let rows = high_scores
+        .records
+        .iter()
+        .take(SHOWN_RECORDS)
+        .enumerate()
+        .flat_map(|(i, record)| {
+            [
+                (i + 1).to_string(),
+                record.mode.name().to_string(),
+                record.hull.hull().name.to_string(),
+                record.seed.to_string(),
+                record.waves.to_string(),
+                record.kills.to_string(),
+                record.score.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    (
+        Name::new("Records Table"),
+        Node {
+            display: Display::Grid,
+            row_gap: px(6),
+            column_gap: px(30),
+            grid_template_columns: RepeatedGridTrack::auto(COLUMNS.len() as u16),
+            ..default()
+        },
+        Children::spawn(SpawnIter(
+            COLUMNS
+                .map(str::to_string)
+                .into_iter()
+                .chain(rows)
+                .map(|text| {
+                    (
+                        widget::label(text),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        },
+                    )
+                }),
+        )),
+    )
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -4,7 +4,7 @@
 
 use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{demo::camera::CameraSettings, menus::Menu, screens::Screen, theme::prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
@@ -15,7 +15,7 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (update_global_volume_label, update_camera_toggle_labels).run_if(in_state(Menu::Settings)),
     );
 }
 
@@ -51,6 +51,22 @@ fn settings_grid() -> impl Bundle {
                 }
             ),
             global_volume_widget(),
+            (
+                widget::label("Screen Shake"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            camera_toggle_widget(CameraToggle::ScreenShake),
+            (
+                widget::label("Heading Up Camera"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            camera_toggle_widget(CameraToggle::HeadingUp),
         ],
     )
 }
@@ -103,6 +119,74 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+enum CameraToggle {
+    ScreenShake,
+    HeadingUp,
+}
+
+impl CameraToggle {
+    fn get(self, settings: &CameraSettings) -> bool {
+        match self {
+            CameraToggle::ScreenShake => settings.screen_shake,
+            CameraToggle::HeadingUp => settings.heading_up,
+        }
+    }
+
+    fn set(self, settings: &mut CameraSettings, on: bool) {
+        match self {
+            CameraToggle::ScreenShake => settings.screen_shake = on,
+            CameraToggle::HeadingUp => settings.heading_up = on,
+        }
+    }
+}
+
+fn camera_toggle_widget(toggle: CameraToggle) -> impl Bundle {
+    (
+        Name::new("Camera Toggle Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small(
+                "-",
+                move |_: On<Pointer<Click>>, mut settings: ResMut<CameraSettings>| {
+                    toggle.set(&mut settings, false);
+                }
+            ),
+            (
+                Name::new("Current Value"),
+                Node {
+                    padding: UiRect::horizontal(px(10)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), CameraToggleLabel(toggle))],
+            ),
+            widget::button_small(
+                "+",
+                move |_: On<Pointer<Click>>, mut settings: ResMut<CameraSettings>| {
+                    toggle.set(&mut settings, true);
+                }
+            ),
+        ],
+    )
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CameraToggleLabel(CameraToggle);
+
+fn update_camera_toggle_labels(
+    settings: Res<CameraSettings>,
+    mut labels: Query<(&CameraToggleLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut labels {
+        text.0 = if label.0.get(&settings) { "On" } else { "Off" }.to_string();
+    }
+}
+
 fn go_back_on_click(
     _: On<Pointer<Click>>,
     screen: Res<State<Screen>>,
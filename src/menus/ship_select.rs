@@ -0,0 +1,61 @@
+//! The ship select menu, shown before a run starts.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::ResourceHandles,
+    demo::ships::{HullKind, SelectedHull},
+    menus::Menu,
+    screens::Screen,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::ShipSelect), spawn_ship_select_menu);
+}
+
+fn spawn_ship_select_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            widget::ui_root("Ship Select Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::ShipSelect),
+            children![widget::header("Choose your ship")],
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Name::new("Ship Cards"),
+                    Node {
+                        column_gap: px(24),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    for kind in HullKind::ALL {
+                        let hull = kind.hull();
+                        parent.spawn(widget::card(
+                            hull.name,
+                            hull.description,
+                            kind.color(),
+                            move |_: On<Pointer<Click>>,
+                                  mut selected: ResMut<SelectedHull>,
+                                  resource_handles: Res<ResourceHandles>,
+                                  mut next_screen: ResMut<NextState<Screen>>| {
+                                selected.0 = kind;
+                                if resource_handles.is_all_done() {
+                                    next_screen.set(Screen::Gameplay);
+                                } else {
+                                    next_screen.set(Screen::Loading);
+                                }
+                            },
+                        ));
+                    }
+                });
+            parent.spawn(widget::button("Back", go_back));
+        });
+}
+
+fn go_back(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
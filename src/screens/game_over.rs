@@ -0,0 +1,152 @@
+//! The screen shown after the player's ship sank, summing up the run.
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+use rand::Rng;
+
+use crate::{
+    demo::{
+        enemy::EnemyKind,
+        high_scores::{LastRun, record_run},
+        run_stats::{RunSeed, RunStats},
+        weapons::WeaponKind,
+    },
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::GameOver),
+        spawn_game_over_screen.after(record_run),
+    );
+}
+
+fn spawn_game_over_screen(mut commands: Commands, stats: Res<RunStats>, last_run: Res<LastRun>) {
+    let (score, color) = if last_run.new_record {
+        (
+            format!("New record: {}", last_run.score),
+            ui_palette::NEW_RECORD_TEXT,
+        )
+    } else {
+        (
+            format!("Score: {}", last_run.score),
+            ui_palette::HEADER_TEXT,
+        )
+    };
+    commands.spawn((
+        widget::ui_root("Game Over Screen"),
+        DespawnOnExit(Screen::GameOver),
+        children![
+            widget::header("Your ship sank"),
+            (
+                Name::new("Score"),
+                Text(score),
+                TextFont::from_font_size(32.0),
+                TextColor(color),
+            ),
+            summary(&stats),
+            (
+                Name::new("Buttons"),
+                Node {
+                    column_gap: px(20),
+                    ..default()
+                },
+                children![
+                    widget::button("Retry", retry),
+                    widget::button("New Seed", retry_with_new_seed),
+                    widget::button("Title", return_to_title),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn summary(stats: &RunStats) -> impl Bundle {
+    let secs = stats.time_survived.as_secs();
+    let mut rows = vec![
+        ("Ship".to_string(), stats.hull.hull().name.to_string()),
+        ("Seed".to_string(), stats.seed.to_string()),
+        (
+            "Time survived".to_string(),
+            format!("{:02}:{:02}", secs / 60, secs % 60),
+        ),
+        ("Waves cleared".to_string(), stats.waves_cleared.to_string()),
+        ("Gold earned".to_string(), stats.gold_earned.to_string()),
+        (
+            "Explored".to_string(),
+            format!("{:.0}%", stats.explored * 100.0),
+        ),
+        (
+            "Accuracy".to_string(),
+            stats.accuracy().map_or("-".to_string(), |accuracy| {
+                format!("{:.0}%", accuracy * 100.0)
+            }),
+        ),
+    ];
+    for kind in EnemyKind::ALL {
+        rows.push((
+            format!("{}s sunk", kind.name()),
+            stats.kills(kind).to_string(),
+        ));
+    }
+    for kind in WeaponKind::ALL {
+        let damage = stats.weapons.get(&kind).map_or(0.0, |weapon| weapon.damage);
+        rows.push((format!("{} damage", kind.name()), format!("{damage:.0}")));
+    }
+    let upgrades = if stats.upgrades.is_empty() {
+        "None".to_string()
+    } else {
+        stats
+            .upgrades
+            .iter()
+            .map(|(name, count)| match count {
+                1 => name.clone(),
+                _ => format!("{name} x{count}"),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    rows.push(("Upgrades".to_string(), upgrades));
+
+    (
+        Name::new("Summary"),
+        Node {
+            display: Display::Grid,
+            row_gap: px(6),
+            column_gap: px(30),
+            grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter(
+            rows.into_iter()
+                .flat_map(|(name, value)| [(name, JustifySelf::End), (value, JustifySelf::Start)])
+                .map(|(text, justify_self)| {
+                    (
+                        widget::label(text),
+                        Node {
+                            justify_self,
+                            ..default()
+                        },
+                    )
+                }),
+        )),
+    )
+}
+
+/// Plays the same world again.
+fn retry(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Gameplay);
+}
+
+fn retry_with_new_seed(
+    _: On<Pointer<Click>>,
+    mut seed: ResMut<RunSeed>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    seed.0 = rand::rng().random();
+    next_screen.set(Screen::Gameplay);
+}
+
+fn return_to_title(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
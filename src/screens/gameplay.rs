@@ -19,11 +19,14 @@ pub(super) fn plugin(app: &mut App) {
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
+                    // An upgrade has to be picked to leave the level-up menu.
+                    .and(not(in_state(Menu::LevelUp)))
                     .and(input_just_pressed(KeyCode::KeyP)),
             ),
         ),
     );
     app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause));
+    app.add_systems(OnEnter(Menu::LevelUp), spawn_pause_overlay);
     app.add_systems(
         OnEnter(Menu::None),
         unpause.run_if(in_state(Screen::Gameplay)),
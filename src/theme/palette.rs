@@ -5,6 +5,8 @@ pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 
 /// #fcfbcc
 pub const HEADER_TEXT: Color = Color::srgb(0.988, 0.984, 0.800);
+/// #f5d24b
+pub const NEW_RECORD_TEXT: Color = Color::srgb(0.961, 0.824, 0.294);
 
 /// #ececec
 pub const BUTTON_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
@@ -14,3 +16,26 @@ pub const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 /// #3d4999
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+
+/// #1e1e28
+pub const BAR_BACKGROUND: Color = Color::srgba(0.118, 0.118, 0.157, 0.8);
+/// #c23b32
+pub const HEALTH_BAR: Color = Color::srgb(0.761, 0.231, 0.196);
+/// #46c7b0
+pub const XP_BAR: Color = Color::srgb(0.275, 0.780, 0.690);
+/// #e6a23c
+pub const ABILITY_BAR: Color = Color::srgb(0.902, 0.635, 0.235);
+/// #f0e6c8
+pub const WEAPON_READY: Color = Color::srgb(0.941, 0.902, 0.784);
+/// #4fa3e0
+pub const SURF_BAR: Color = Color::srgb(0.310, 0.639, 0.878);
+/// #e0503c
+pub const ENEMY_MARKER: Color = Color::srgb(0.878, 0.314, 0.235);
+/// #b44be1
+pub const BOSS_MARKER: Color = Color::srgb(0.706, 0.294, 0.882);
+/// #f5d24b
+pub const OBJECTIVE_MARKER: Color = Color::srgb(0.961, 0.824, 0.294);
+/// #64c878
+pub const PORT_MARKER: Color = Color::srgb(0.392, 0.784, 0.471);
+/// #a0d2f0
+pub const WIND_MARKER: Color = Color::srgb(0.627, 0.824, 0.941);
@@ -9,6 +9,10 @@ use bevy::{
 
 use crate::theme::{interaction::InteractionPalette, palette::*};
 
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, apply_bar_fill);
+}
+
 /// A root UI node that fills the window and centers its content.
 pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
     (
@@ -132,3 +136,129 @@ where
         })),
     )
 }
+
+/// A tall clickable card with a title, a description and a colored accent
+/// strip, with an action defined as an [`Observer`].
+pub fn card<E, B, M, I>(
+    title: impl Into<String>,
+    description: impl Into<String>,
+    accent: Color,
+    action: I,
+) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    let title = title.into();
+    let description = description.into();
+    let action = IntoObserverSystem::into_system(action);
+    (
+        Name::new("Card"),
+        Node::default(),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent
+                .spawn((
+                    Name::new("Card Inner"),
+                    Button,
+                    Node {
+                        width: px(260),
+                        height: px(340),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: px(16),
+                        padding: UiRect::all(px(16)),
+                        border: UiRect::top(px(10)),
+                        ..default()
+                    },
+                    BorderColor::all(accent),
+                    BorderRadius::all(px(12)),
+                    BackgroundColor(BUTTON_BACKGROUND),
+                    InteractionPalette {
+                        none: BUTTON_BACKGROUND,
+                        hovered: BUTTON_HOVERED_BACKGROUND,
+                        pressed: BUTTON_PRESSED_BACKGROUND,
+                    },
+                    children![
+                        (
+                            Name::new("Card Title"),
+                            Text(title),
+                            TextFont::from_font_size(30.0),
+                            TextColor(BUTTON_TEXT),
+                            TextLayout::new_with_justify(Justify::Center),
+                            Pickable::IGNORE,
+                        ),
+                        (
+                            Name::new("Card Description"),
+                            Text(description),
+                            TextFont::from_font_size(20.0),
+                            TextColor(LABEL_TEXT),
+                            TextLayout::new_with_justify(Justify::Center),
+                            Pickable::IGNORE,
+                        ),
+                    ],
+                ))
+                .observe(action);
+        })),
+    )
+}
+
+/// How far a [`bar`] is filled, between 0 and 1.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Bar(pub f32);
+
+/// A horizontal bar, filled according to its [`Bar`] component.
+pub fn bar(
+    name: impl Into<Cow<'static, str>>,
+    width: Val,
+    height: Val,
+    color: Color,
+) -> impl Bundle {
+    (
+        Name::new(name),
+        Bar(1.0),
+        Node {
+            width,
+            height,
+            ..default()
+        },
+        BackgroundColor(BAR_BACKGROUND),
+        children![(
+            Name::new("Bar Fill"),
+            Node {
+                width: percent(100),
+                height: percent(100),
+                ..default()
+            },
+            BackgroundColor(color),
+        )],
+    )
+}
+
+/// A small square in a solid color, e.g. a status pip.
+pub fn icon(name: impl Into<Cow<'static, str>>, size: Val, color: Color) -> impl Bundle {
+    (
+        Name::new(name),
+        Node {
+            width: size,
+            height: size,
+            ..default()
+        },
+        BorderRadius::all(px(3)),
+        BackgroundColor(color),
+    )
+}
+
+fn apply_bar_fill(
+    bars: Query<(&Bar, &Children), Changed<Bar>>,
+    mut nodes: Query<&mut Node, Without<Bar>>,
+) {
+    for (bar, children) in &bars {
+        for child in children {
+            if let Ok(mut node) = nodes.get_mut(*child) {
+                node.width = percent(bar.0.clamp(0.0, 1.0) * 100.0);
+            }
+        }
+    }
+}